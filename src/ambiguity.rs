@@ -0,0 +1,130 @@
+use super::prelude::*;
+use super::search::find_solutions;
+use super::{CellState, Nonogram};
+use ndarray::Array2;
+
+/// Where a puzzle's clues leave the picture open.
+#[derive(Clone, Debug)]
+pub struct AmbiguityReport {
+    /// Cells line propagation alone could not determine, as `(row, column)`.
+    pub unknown: Vec<(usize, usize)>,
+    /// Solutions found by searching past where propagation stalled, as grids of 0s and 1s.
+    pub solutions: Vec<Array2<u8>>,
+    /// Whether the search explored every possibility, so `solutions` is the full list.
+    pub complete: bool,
+    /// Cells that aren't the same in every solution found.
+    pub differing: Vec<(usize, usize)>,
+    /// `differing` split into groups of cells that touch along an edge.
+    pub regions: Vec<Vec<(usize, usize)>>,
+}
+
+impl AmbiguityReport {
+    /// Whether the clues have exactly one solution, even if line logic alone can't find it.
+    pub fn is_unique(&self) -> bool {
+        self.complete && self.solutions.len() == 1
+    }
+}
+
+/// Splits cells into groups connected through their four neighbours.
+fn connected_regions(cells: &[(usize, usize)], dim: (usize, usize)) -> Vec<Vec<(usize, usize)>> {
+    let mut pending = Array2::from_elem(dim, false);
+    let mut regions = Vec::new();
+
+    for &cell in cells {
+        pending[cell] = true;
+    }
+
+    for &start in cells {
+        if !pending[start] {
+            continue;
+        }
+
+        let mut region = Vec::new();
+        let mut stack = vec![start];
+
+        pending[start] = false;
+
+        while let Some((i, j)) = stack.pop() {
+            region.push((i, j));
+
+            let neighbours = [
+                (i.wrapping_sub(1), j),
+                (i + 1, j),
+                (i, j.wrapping_sub(1)),
+                (i, j + 1),
+            ];
+
+            for &neighbour in &neighbours {
+                if pending.get(neighbour) == Some(&true) {
+                    pending[neighbour] = false;
+                    stack.push(neighbour);
+                }
+            }
+        }
+
+        region.sort();
+        regions.push(region);
+    }
+
+    regions
+}
+
+impl Nonogram {
+    /// Reports which cells the clues leave undetermined.
+    ///
+    /// Runs line propagation first, then searches for up to `max_solutions` solutions and
+    /// compares them. With a limit of at least 2 a unique picture is told apart from an ambiguous
+    /// one; higher limits find more of the cells that can vary.
+    pub fn ambiguity(&self, max_solutions: usize) -> AmbiguityReport {
+        let unknown = self
+            .solve()
+            .grid
+            .indexed_iter()
+            .filter(|&(_, &cell)| cell == CellState::Unknown)
+            .map(|(index, _)| index)
+            .collect();
+        let search = find_solutions(self, max_solutions);
+        let solutions: Vec<Array2<u8>> = search
+            .solutions
+            .iter()
+            .map(|grid| grid.map(|&cell| (cell == CellState::Filled) as u8))
+            .collect();
+        let differing: Vec<(usize, usize)> = match solutions.first() {
+            Some(first) => first
+                .indexed_iter()
+                .filter(|&(index, &cell)| solutions.iter().any(|other| other[index] != cell))
+                .map(|(index, _)| index)
+                .collect(),
+            None => Vec::new(),
+        };
+        let regions = connected_regions(&differing, (self.height(), self.width()));
+
+        AmbiguityReport {
+            unknown,
+            solutions,
+            complete: search.complete,
+            differing,
+            regions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::connected_regions;
+
+    #[test]
+    fn regions_join_edge_neighbours() {
+        let cells = [(0, 0), (0, 1), (1, 1), (2, 3), (3, 3), (3, 0)];
+        let regions = connected_regions(&cells, (4, 4));
+
+        assert_eq!(
+            regions,
+            vec![
+                vec![(0, 0), (0, 1), (1, 1)],
+                vec![(2, 3), (3, 3)],
+                vec![(3, 0)]
+            ]
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use crossterm::event::KeyCode;
+use nonogram::{CellState, Progress};
+
+/// Requests that need the terminal or the file system, left to the caller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Save,
+    Quit,
+}
+
+/// A game being played: the player's progress along with the cursor and the status line.
+pub struct Game {
+    pub progress: Progress,
+    pub row: usize,
+    pub column: usize,
+    pub show_mistakes: bool,
+    pub status: String,
+}
+
+impl Game {
+    pub fn new(progress: Progress) -> Game {
+        Game {
+            progress,
+            row: 0,
+            column: 0,
+            show_mistakes: false,
+            status: String::new(),
+        }
+    }
+
+    /// Handles a key press, keeping the cursor on the grid.
+    pub fn press(&mut self, key: KeyCode) -> Option<Command> {
+        let (height, width) = (
+            self.progress.puzzle().height(),
+            self.progress.puzzle().width(),
+        );
+
+        self.status.clear();
+
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => self.row = self.row.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.row = (self.row + 1).min(height.saturating_sub(1))
+            }
+            KeyCode::Left | KeyCode::Char('h') => self.column = self.column.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.column = (self.column + 1).min(width.saturating_sub(1))
+            }
+            KeyCode::Char(' ') | KeyCode::Char('f') => {
+                self.progress
+                    .toggle(self.row, self.column, CellState::Filled);
+            }
+            KeyCode::Char('x') => {
+                self.progress
+                    .toggle(self.row, self.column, CellState::Empty);
+            }
+            KeyCode::Backspace | KeyCode::Delete => {
+                self.progress.set(self.row, self.column, CellState::Unknown);
+            }
+            KeyCode::Char('u') => {
+                self.progress.undo();
+            }
+            KeyCode::Char('r') => {
+                self.progress.redo();
+            }
+            KeyCode::Char('c') => {
+                self.show_mistakes = !self.show_mistakes;
+
+                if self.show_mistakes {
+                    self.status = format!("{} mistakes", self.progress.mistakes().len());
+                }
+            }
+            KeyCode::Char('s') => return Some(Command::Save),
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Command::Quit),
+            _ => (),
+        }
+
+        if self.progress.is_complete() {
+            self.status = String::from("solved! press q to quit");
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Command, Game};
+    use crossterm::event::KeyCode;
+    use nonogram::{arr2, CellState, Nonogram, Progress};
+
+    fn game() -> Game {
+        Game::new(Progress::new(Nonogram::from_grid(arr2(&[
+            [1, 0, 1],
+            [0, 1, 0],
+        ]))))
+    }
+
+    #[test]
+    fn cursor_stays_on_the_grid() {
+        let mut game = game();
+
+        game.press(KeyCode::Up);
+        game.press(KeyCode::Char('h'));
+        assert_eq!((game.row, game.column), (0, 0));
+
+        for _ in 0..5 {
+            game.press(KeyCode::Down);
+            game.press(KeyCode::Char('l'));
+        }
+
+        assert_eq!((game.row, game.column), (1, 2));
+    }
+
+    #[test]
+    fn marks_and_clears_cells() {
+        let mut game = game();
+
+        game.press(KeyCode::Char(' '));
+        assert_eq!(game.progress.cells()[[0, 0]], CellState::Filled);
+        game.press(KeyCode::Char('x'));
+        assert_eq!(game.progress.cells()[[0, 0]], CellState::Empty);
+        game.press(KeyCode::Char('x'));
+        assert_eq!(game.progress.cells()[[0, 0]], CellState::Unknown);
+
+        game.press(KeyCode::Char('f'));
+        game.press(KeyCode::Backspace);
+        assert_eq!(game.progress.cells()[[0, 0]], CellState::Unknown);
+
+        game.press(KeyCode::Char('u'));
+        assert_eq!(game.progress.cells()[[0, 0]], CellState::Filled);
+        game.press(KeyCode::Char('r'));
+        assert_eq!(game.progress.cells()[[0, 0]], CellState::Unknown);
+    }
+
+    #[test]
+    fn checking_counts_mistakes() {
+        let mut game = game();
+
+        game.press(KeyCode::Char('l'));
+        game.press(KeyCode::Char(' '));
+        game.press(KeyCode::Char('c'));
+        assert!(game.show_mistakes);
+        assert_eq!(game.status, "1 mistakes");
+
+        game.press(KeyCode::Char('c'));
+        assert!(!game.show_mistakes);
+        assert!(game.status.is_empty());
+    }
+
+    #[test]
+    fn detects_a_win() {
+        let mut game = game();
+
+        for key in [' ', 'l', 'l', ' ', 'j', 'h'] {
+            game.press(KeyCode::Char(key));
+        }
+
+        assert!(game.status.is_empty());
+        game.press(KeyCode::Char(' '));
+        assert!(game.progress.is_complete());
+        assert_eq!(game.status, "solved! press q to quit");
+    }
+
+    #[test]
+    fn leaves_saving_and_quitting_to_the_caller() {
+        let mut game = game();
+
+        assert_eq!(game.press(KeyCode::Char('s')), Some(Command::Save));
+        assert_eq!(game.press(KeyCode::Esc), Some(Command::Quit));
+        assert_eq!(game.press(KeyCode::Char('z')), None);
+    }
+}
@@ -0,0 +1,116 @@
+mod game;
+mod ui;
+
+use clap::Parser;
+use crossterm::event::{self, Event, KeyEventKind};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute};
+use game::{Command, Game};
+use nonogram::{GenerateOptions, Nonogram, Progress};
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Play a nonogram in the terminal.
+///
+/// Starts a random uniquely solvable puzzle unless a puzzle file or a saved game is given.
+#[derive(Parser)]
+#[command(name = "nonogram-tui", version)]
+struct Cli {
+    /// A puzzle stored as json.
+    puzzle: Option<PathBuf>,
+    /// Resume a game saved with `s`.
+    #[arg(long, conflicts_with = "puzzle")]
+    resume: Option<PathBuf>,
+    /// Where `s` saves the game.
+    #[arg(long, default_value = "nonogram-save.json")]
+    save: PathBuf,
+    #[arg(long, default_value_t = 10)]
+    width: usize,
+    #[arg(long, default_value_t = 10)]
+    height: usize,
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+fn start(cli: &Cli) -> Result<Progress, Box<dyn Error>> {
+    if let Some(path) = &cli.resume {
+        return Ok(serde_json::from_slice(&fs::read(path)?)?);
+    }
+
+    if let Some(path) = &cli.puzzle {
+        return Ok(Progress::new(Nonogram::from_json(&fs::read_to_string(
+            path,
+        )?)?));
+    }
+
+    let options = GenerateOptions {
+        seed: cli.seed,
+        require_unique: true,
+        ..GenerateOptions::new(cli.width, cli.height)
+    };
+
+    match Nonogram::generate_with(&options) {
+        Some(puzzle) => Ok(Progress::new(puzzle)),
+        None => Err("could not generate a uniquely solvable puzzle".into()),
+    }
+}
+
+fn save(progress: &Progress, path: &Path) -> Result<(), Box<dyn Error>> {
+    fs::write(path, serde_json::to_vec(progress)?)?;
+    Ok(())
+}
+
+fn play<W: Write>(out: &mut W, game: &mut Game, cli: &Cli) -> Result<(), Box<dyn Error>> {
+    loop {
+        ui::draw(out, game)?;
+
+        let key = match event::read()? {
+            Event::Key(key) if key.kind != KeyEventKind::Release => key,
+            _ => continue,
+        };
+
+        match game.press(key.code) {
+            Some(Command::Save) => {
+                game.status = match save(&game.progress, &cli.save) {
+                    Ok(()) => format!("saved to {}", cli.save.display()),
+                    Err(e) => format!("could not save: {}", e),
+                };
+            }
+            Some(Command::Quit) => return Ok(()),
+            None => (),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut game = match start(&cli) {
+        Ok(progress) => Game::new(progress),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut out = io::stdout();
+    let result = terminal::enable_raw_mode()
+        .map_err(Box::<dyn Error>::from)
+        .and_then(|()| {
+            execute!(out, EnterAlternateScreen, cursor::Hide)?;
+            play(&mut out, &mut game, &cli)
+        });
+
+    let _ = execute!(out, cursor::Show, LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
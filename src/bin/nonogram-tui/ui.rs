@@ -0,0 +1,135 @@
+use crate::game::Game;
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::terminal::{Clear, ClearType};
+use crossterm::{cursor::MoveTo, queue};
+use nonogram::CellState;
+use std::io::{self, Write};
+
+const CELL_WIDTH: usize = 3;
+const HELP: &str = "arrows/hjkl move  space fill  x cross  backspace clear  u undo  r redo  c check  s save  q quit";
+
+/// Pairs each number of a clue with whether the player has finished that segment. Empty clues
+/// are shown as a single 0 that is finished once the line is.
+fn clue_parts(clue: &[usize], completed: Vec<bool>, satisfied: bool) -> Vec<(String, bool)> {
+    if clue.is_empty() {
+        vec![(String::from("0"), satisfied)]
+    } else {
+        clue.iter()
+            .map(|segment| segment.to_string())
+            .zip(completed)
+            .collect()
+    }
+}
+
+fn clue_color(done: bool) -> Color {
+    if done {
+        Color::DarkGrey
+    } else {
+        Color::Reset
+    }
+}
+
+pub fn draw<W: Write>(out: &mut W, game: &Game) -> io::Result<()> {
+    let progress = &game.progress;
+    let puzzle = progress.puzzle();
+    let row_clues: Vec<Vec<(String, bool)>> = (0..puzzle.height())
+        .map(|i| {
+            clue_parts(
+                &puzzle.row_segments[i],
+                progress.completed_row_segments(i),
+                progress.row_satisfied(i),
+            )
+        })
+        .collect();
+    let column_clues: Vec<Vec<(String, bool)>> = (0..puzzle.width())
+        .map(|j| {
+            clue_parts(
+                &puzzle.column_segments[j],
+                progress.completed_column_segments(j),
+                progress.column_satisfied(j),
+            )
+        })
+        .collect();
+    let margin = row_clues
+        .iter()
+        .map(|clue| clue.iter().map(|(text, _)| text.len() + 1).sum::<usize>())
+        .max()
+        .unwrap_or(0);
+    let clue_rows = column_clues.iter().map(Vec::len).max().unwrap_or(0);
+    let mistakes = progress.mistakes();
+
+    queue!(out, Clear(ClearType::All))?;
+
+    for line in 0..clue_rows {
+        queue!(out, MoveTo(margin as u16, line as u16))?;
+
+        for clue in &column_clues {
+            let offset = clue_rows - clue.len();
+            let (text, done) = if line >= offset {
+                (clue[line - offset].0.as_str(), clue[line - offset].1)
+            } else {
+                ("", false)
+            };
+
+            queue!(
+                out,
+                SetForegroundColor(clue_color(done)),
+                Print(format!("{:>width$}", text, width = CELL_WIDTH)),
+                ResetColor
+            )?;
+        }
+    }
+
+    for (i, clue) in row_clues.iter().enumerate() {
+        let y = (clue_rows + i) as u16;
+        let width: usize = clue.iter().map(|(text, _)| text.len() + 1).sum();
+
+        queue!(out, MoveTo((margin - width) as u16, y))?;
+
+        for (text, done) in clue {
+            queue!(
+                out,
+                SetForegroundColor(clue_color(*done)),
+                Print(format!("{} ", text)),
+                ResetColor
+            )?;
+        }
+
+        for j in 0..puzzle.width() {
+            let symbol = match progress.cells()[[i, j]] {
+                CellState::Filled => " ██",
+                CellState::Empty => "  ×",
+                CellState::Unknown => "  ·",
+            };
+
+            if game.show_mistakes && mistakes.contains(&(i, j)) {
+                queue!(out, SetForegroundColor(Color::Red))?;
+            }
+
+            if (i, j) == (game.row, game.column) {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+
+            queue!(
+                out,
+                Print(symbol),
+                SetAttribute(Attribute::Reset),
+                ResetColor
+            )?;
+        }
+    }
+
+    let footer = (clue_rows + puzzle.height() + 1) as u16;
+
+    queue!(
+        out,
+        MoveTo(0, footer),
+        Print(&game.status),
+        MoveTo(0, footer + 1),
+        SetForegroundColor(Color::DarkGrey),
+        Print(HELP),
+        ResetColor
+    )?;
+
+    out.flush()
+}
@@ -0,0 +1,71 @@
+use clap::ValueEnum;
+use nonogram::Nonogram;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// The storage formats the command line tool reads and writes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Json,
+    Cbor,
+    Msgpack,
+    Bincode,
+}
+
+impl Format {
+    /// Picks a format from `explicit` if given, otherwise from the file extension, falling back
+    /// to json.
+    pub fn resolve(explicit: Option<Format>, path: &Path) -> Format {
+        explicit.unwrap_or_else(|| {
+            match path.extension().and_then(|extension| extension.to_str()) {
+                Some("cbor") => Format::Cbor,
+                Some("msgpack") | Some("mp") => Format::Msgpack,
+                Some("bin") | Some("bincode") => Format::Bincode,
+                _ => Format::Json,
+            }
+        })
+    }
+
+    pub fn decode(self, bytes: &[u8]) -> Result<Nonogram, Box<dyn Error>> {
+        Ok(match self {
+            Format::Json => Nonogram::from_json(std::str::from_utf8(bytes)?)?,
+            Format::Cbor => Nonogram::from_cbor(bytes)?,
+            Format::Msgpack => Nonogram::from_msgpack(bytes)?,
+            Format::Bincode => Nonogram::from_bincode(bytes)?,
+        })
+    }
+
+    pub fn encode(self, nonogram: &Nonogram) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(match self {
+            Format::Json => nonogram.as_json()?.into_bytes(),
+            Format::Cbor => nonogram.as_cbor()?,
+            Format::Msgpack => nonogram.as_msgpack()?,
+            Format::Bincode => nonogram.as_bincode()?,
+        })
+    }
+}
+
+/// Reads the contents of `path`, or standard input when the path is `-`.
+pub fn read_input(path: &Path) -> io::Result<Vec<u8>> {
+    if path == Path::new("-") {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+        Ok(bytes)
+    } else {
+        fs::read(path)
+    }
+}
+
+/// Writes `bytes` to `path`, or standard output when there is no path or it is `-`.
+pub fn write_output(path: Option<&Path>, bytes: &[u8]) -> io::Result<()> {
+    match path {
+        Some(path) if path != Path::new("-") => fs::write(path, bytes),
+        _ => io::stdout().write_all(bytes),
+    }
+}
+
+pub fn read_puzzle(path: &Path, format: Option<Format>) -> Result<Nonogram, Box<dyn Error>> {
+    Format::resolve(format, path).decode(&read_input(path)?)
+}
@@ -0,0 +1,388 @@
+mod format;
+mod render;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use format::{read_puzzle, write_output, Format};
+use nonogram::{
+    Array2, CellState, Difficulty, GenerateOptions, Nonogram, Pattern, SolveOptions, SolveStatus,
+    Symmetry,
+};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+/// Generate, solve, convert and render nonogram puzzles.
+///
+/// Puzzle files are read as json unless `--format` says otherwise or the file extension is one
+/// of `.cbor`, `.msgpack`/`.mp` or `.bin`/`.bincode`. A path of `-` means standard input.
+#[derive(Parser)]
+#[command(name = "nonogram", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a random puzzle.
+    Generate {
+        #[arg(long)]
+        width: usize,
+        #[arg(long)]
+        height: usize,
+        /// Seed for reproducible puzzles.
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Probability of any single cell being filled.
+        #[arg(long, default_value_t = 0.5)]
+        density: f64,
+        /// Only accept puzzles the solver can finish, which guarantees a unique solution.
+        #[arg(long)]
+        unique: bool,
+        /// The kind of picture to draw.
+        #[arg(long, value_enum, default_value_t = Shape::Uniform)]
+        pattern: Shape,
+        /// Only accept puzzles of this difficulty.
+        #[arg(long, value_enum)]
+        difficulty: Option<Level>,
+        /// How many grids to try when `--unique` or `--difficulty` is set.
+        #[arg(long, default_value_t = 1000)]
+        attempts: usize,
+        #[arg(long)]
+        format: Option<Format>,
+        /// Where to write the puzzle. Defaults to standard output.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Solve a puzzle from its clues and print the result, or where the solver stalled.
+    Solve {
+        input: PathBuf,
+        #[arg(long)]
+        format: Option<Format>,
+        /// Give up after this many seconds.
+        #[arg(long, value_parser = parse_timeout)]
+        timeout: Option<Duration>,
+    },
+    /// Translate a puzzle between storage formats.
+    Convert {
+        input: PathBuf,
+        output: PathBuf,
+        #[arg(long)]
+        from: Option<Format>,
+        #[arg(long)]
+        to: Option<Format>,
+    },
+    /// Draw a puzzle with its clues.
+    Render {
+        input: PathBuf,
+        #[arg(long)]
+        format: Option<Format>,
+        #[arg(long, value_enum, default_value_t = Style::Ascii)]
+        style: Style,
+        /// Leave the grid empty, for printing the puzzle rather than its answer.
+        #[arg(long)]
+        blank: bool,
+        /// Cell size in pixels for SVG output.
+        #[arg(long, default_value_t = 20)]
+        cell_size: usize,
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Check that a puzzle's clues match its grid.
+    Validate {
+        input: PathBuf,
+        #[arg(long)]
+        format: Option<Format>,
+        /// Also require the solver to be able to finish the puzzle.
+        #[arg(long)]
+        solvable: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Style {
+    Ascii,
+    Svg,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Shape {
+    Uniform,
+    /// Left and right halves mirror each other.
+    Horizontal,
+    /// Top and bottom halves mirror each other.
+    Vertical,
+    /// The same when turned upside down.
+    Rotational,
+    /// Blobs smoothed out of random cells.
+    Smooth,
+    /// A few large shapes drawn from value noise.
+    Noise,
+}
+
+impl From<Shape> for Pattern {
+    fn from(shape: Shape) -> Pattern {
+        match shape {
+            Shape::Uniform => Pattern::Uniform,
+            Shape::Horizontal => Pattern::Symmetric(Symmetry::Horizontal),
+            Shape::Vertical => Pattern::Symmetric(Symmetry::Vertical),
+            Shape::Rotational => Pattern::Symmetric(Symmetry::Rotational),
+            Shape::Smooth => Pattern::Smoothed { iterations: 3 },
+            Shape::Noise => Pattern::Noise { scale: 4 },
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Level {
+    Trivial,
+    Easy,
+    Medium,
+    /// Needs probing, but no backtracking.
+    Hard,
+    /// Needs backtracking.
+    Expert,
+}
+
+impl From<Level> for Difficulty {
+    fn from(level: Level) -> Difficulty {
+        match level {
+            Level::Trivial => Difficulty::Trivial,
+            Level::Easy => Difficulty::Easy,
+            Level::Medium => Difficulty::Medium,
+            Level::Hard => Difficulty::Hard,
+            Level::Expert => Difficulty::Expert,
+        }
+    }
+}
+
+fn generate(
+    options: &GenerateOptions,
+    format: Format,
+    output: Option<&Path>,
+) -> Result<ExitCode, Box<dyn Error>> {
+    if !(0.0..=1.0).contains(&options.density) {
+        return Err("density must be between 0 and 1".into());
+    }
+
+    match Nonogram::generate_with(options) {
+        Some(nonogram) => {
+            write_output(output, &format.encode(&nonogram)?)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        None => {
+            let wanted = match &options.difficulty {
+                Some(band) => format!("{:?}", band.start()).to_lowercase(),
+                None => "uniquely solvable".to_string(),
+            };
+
+            eprintln!(
+                "no {} puzzle found in {} attempts",
+                wanted, options.max_attempts
+            );
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+/// Reads `--timeout` as seconds, rejecting negative and non-finite values.
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    let seconds: f64 = value
+        .parse()
+        .map_err(|_| format!("`{}` is not a number", value))?;
+
+    Duration::try_from_secs_f64(seconds).map_err(|_| {
+        format!(
+            "`{}` is not a finite, non-negative number of seconds",
+            value
+        )
+    })
+}
+
+fn solve(
+    input: &Path,
+    format: Option<Format>,
+    timeout: Option<Duration>,
+) -> Result<ExitCode, Box<dyn Error>> {
+    let nonogram = read_puzzle(input, format)?;
+    let options = SolveOptions {
+        max_time: timeout,
+        ..SolveOptions::default()
+    };
+    let result = nonogram.solve_with_options(&options);
+
+    print!("{}", render::ascii(&nonogram, &result.grid, '?'));
+
+    match result.status {
+        SolveStatus::Solved => {
+            println!("solved");
+            Ok(ExitCode::SUCCESS)
+        }
+        SolveStatus::Stalled => {
+            let unknown = result
+                .grid
+                .iter()
+                .filter(|&&cell| cell == CellState::Unknown)
+                .count();
+            println!("stalled with {} undetermined cells", unknown);
+            Ok(ExitCode::FAILURE)
+        }
+        SolveStatus::Contradiction => {
+            println!("the clues contradict each other");
+            Ok(ExitCode::FAILURE)
+        }
+        SolveStatus::BudgetExceeded => {
+            let seconds = timeout.unwrap_or_default().as_secs_f64();
+            println!("gave up after {} seconds", seconds);
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+fn convert(
+    input: &Path,
+    output: &Path,
+    from: Option<Format>,
+    to: Option<Format>,
+) -> Result<ExitCode, Box<dyn Error>> {
+    let nonogram = read_puzzle(input, from)?;
+
+    write_output(
+        Some(output),
+        &Format::resolve(to, output).encode(&nonogram)?,
+    )?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn render(
+    input: &Path,
+    format: Option<Format>,
+    style: Style,
+    blank: bool,
+    cell_size: usize,
+    output: Option<&Path>,
+) -> Result<ExitCode, Box<dyn Error>> {
+    let nonogram = read_puzzle(input, format)?;
+    let cells = if blank || nonogram.is_clue_only() {
+        Array2::default((nonogram.height(), nonogram.width()))
+    } else {
+        render::completed_cells(&nonogram)
+    };
+
+    let drawing = match style {
+        Style::Ascii => render::ascii(&nonogram, &cells, ' '),
+        Style::Svg => render::svg(&nonogram, &cells, cell_size),
+    };
+
+    write_output(output, drawing.as_bytes())?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn validate(
+    input: &Path,
+    format: Option<Format>,
+    solvable: bool,
+) -> Result<ExitCode, Box<dyn Error>> {
+    let nonogram = match read_puzzle(input, format) {
+        Ok(nonogram) => nonogram,
+        Err(e) => {
+            println!("invalid: {}", e);
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+
+    if solvable && !nonogram.solvable() {
+        let report = nonogram.ambiguity(2);
+
+        println!("valid, but the solver cannot finish it");
+
+        if report.is_unique() {
+            println!("its solution is unique, but line logic alone cannot reach it");
+        }
+
+        for region in &report.regions {
+            let (row, column) = region[0];
+
+            println!(
+                "{} cells can change together starting at row {}, column {}",
+                region.len(),
+                row,
+                column
+            );
+        }
+
+        return Ok(ExitCode::FAILURE);
+    }
+
+    println!("valid");
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Generate {
+            width,
+            height,
+            seed,
+            density,
+            unique,
+            pattern,
+            difficulty,
+            attempts,
+            format,
+            output,
+        } => {
+            let options = GenerateOptions {
+                density,
+                seed,
+                require_unique: unique,
+                pattern: pattern.into(),
+                max_attempts: attempts,
+                difficulty: difficulty.map(|level| {
+                    let difficulty = Difficulty::from(level);
+
+                    difficulty..=difficulty
+                }),
+                ..GenerateOptions::new(width, height)
+            };
+            let format = match &output {
+                Some(path) => Format::resolve(format, path),
+                None => format.unwrap_or(Format::Json),
+            };
+
+            generate(&options, format, output.as_deref())
+        }
+        Command::Solve {
+            input,
+            format,
+            timeout,
+        } => solve(&input, format, timeout),
+        Command::Convert {
+            input,
+            output,
+            from,
+            to,
+        } => convert(&input, &output, from, to),
+        Command::Render {
+            input,
+            format,
+            style,
+            blank,
+            cell_size,
+            output,
+        } => render(&input, format, style, blank, cell_size, output.as_deref()),
+        Command::Validate {
+            input,
+            format,
+            solvable,
+        } => validate(&input, format, solvable),
+    };
+
+    result.unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        ExitCode::from(2)
+    })
+}
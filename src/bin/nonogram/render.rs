@@ -0,0 +1,202 @@
+use nonogram::{Array2, CellState, Nonogram};
+use std::fmt::Write;
+
+fn clue_text(clue: &[usize]) -> Vec<String> {
+    if clue.is_empty() {
+        vec![String::from("0")]
+    } else {
+        clue.iter().map(|segment| segment.to_string()).collect()
+    }
+}
+
+/// The puzzle's own picture as cell states.
+pub fn completed_cells(nonogram: &Nonogram) -> Array2<CellState> {
+    nonogram.completed_grid.map(|&cell| {
+        if cell == 1 {
+            CellState::Filled
+        } else {
+            CellState::Empty
+        }
+    })
+}
+
+/// Draws the clues in the margins and `cells` in the grid as plain text.
+///
+/// Filled cells are drawn as `#`, empty ones as `.` and unknown ones as `unknown`.
+pub fn ascii(nonogram: &Nonogram, cells: &Array2<CellState>, unknown: char) -> String {
+    let row_clues: Vec<String> = nonogram
+        .row_segments
+        .iter()
+        .map(|clue| clue_text(clue).join(" "))
+        .collect();
+    let column_clues: Vec<Vec<String>> = nonogram
+        .column_segments
+        .iter()
+        .map(|clue| clue_text(clue))
+        .collect();
+
+    let margin = row_clues.iter().map(String::len).max().unwrap_or(0);
+    let cell_width = column_clues
+        .iter()
+        .flatten()
+        .map(|number| number.len() + 1)
+        .max()
+        .unwrap_or(2)
+        .max(2);
+    let clue_rows = column_clues.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut output = String::new();
+
+    for line in 0..clue_rows {
+        output.push_str(&" ".repeat(margin + 1));
+
+        for clue in &column_clues {
+            let offset = clue_rows - clue.len();
+            let text = if line >= offset {
+                clue[line - offset].as_str()
+            } else {
+                ""
+            };
+
+            write!(output, "{:>width$}", text, width = cell_width).unwrap();
+        }
+
+        output.push('\n');
+    }
+
+    for (index, row) in cells.rows().into_iter().enumerate() {
+        write!(output, "{:>width$} ", row_clues[index], width = margin).unwrap();
+
+        for cell in row.iter() {
+            let symbol = match cell {
+                CellState::Filled => '#',
+                CellState::Empty => '.',
+                CellState::Unknown => unknown,
+            };
+
+            write!(output, "{:>width$}", symbol, width = cell_width).unwrap();
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Draws the clues in the margins and `cells` in the grid as an SVG image.
+pub fn svg(nonogram: &Nonogram, cells: &Array2<CellState>, cell_size: usize) -> String {
+    let left = nonogram
+        .row_segments
+        .iter()
+        .map(|clue| clue.len().max(1))
+        .max()
+        .unwrap_or(1)
+        * cell_size;
+    let top = nonogram
+        .column_segments
+        .iter()
+        .map(|clue| clue.len().max(1))
+        .max()
+        .unwrap_or(1)
+        * cell_size;
+    let width = left + nonogram.width() * cell_size;
+    let height = top + nonogram.height() * cell_size;
+    let font_size = cell_size * 3 / 5;
+
+    let mut output = String::new();
+
+    writeln!(
+        output,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = width + 1,
+        h = height + 1
+    )
+    .unwrap();
+    writeln!(
+        output,
+        "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n<g font-family=\"sans-serif\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">",
+        font_size
+    )
+    .unwrap();
+
+    for (index, clue) in nonogram.row_segments.iter().enumerate() {
+        let text = clue_text(clue);
+        let y = top + index * cell_size + cell_size / 2;
+
+        for (position, number) in text.iter().enumerate() {
+            let x = left - (text.len() - position) * cell_size + cell_size / 2;
+            writeln!(output, "<text x=\"{}\" y=\"{}\">{}</text>", x, y, number).unwrap();
+        }
+    }
+
+    for (index, clue) in nonogram.column_segments.iter().enumerate() {
+        let text = clue_text(clue);
+        let x = left + index * cell_size + cell_size / 2;
+
+        for (position, number) in text.iter().enumerate() {
+            let y = top - (text.len() - position) * cell_size + cell_size / 2;
+            writeln!(output, "<text x=\"{}\" y=\"{}\">{}</text>", x, y, number).unwrap();
+        }
+    }
+
+    output.push_str("</g>\n");
+
+    for ((row, column), cell) in cells.indexed_iter() {
+        let x = left + column * cell_size;
+        let y = top + row * cell_size;
+
+        match cell {
+            CellState::Filled => writeln!(
+                output,
+                "<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"black\"/>",
+                x,
+                y,
+                s = cell_size
+            )
+            .unwrap(),
+            CellState::Empty => writeln!(
+                output,
+                "<path d=\"M{x0} {y0}L{x1} {y1}M{x1} {y0}L{x0} {y1}\" stroke=\"gray\"/>",
+                x0 = x + cell_size / 4,
+                y0 = y + cell_size / 4,
+                x1 = x + cell_size * 3 / 4,
+                y1 = y + cell_size * 3 / 4
+            )
+            .unwrap(),
+            CellState::Unknown => (),
+        }
+    }
+
+    for row in 0..=nonogram.height() {
+        let stroke = if row % 5 == 0 { 2 } else { 1 };
+        let y = top + row * cell_size;
+
+        writeln!(
+            output,
+            "<line x1=\"{}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" stroke=\"black\" stroke-width=\"{}\"/>",
+            left,
+            width,
+            stroke,
+            y = y
+        )
+        .unwrap();
+    }
+
+    for column in 0..=nonogram.width() {
+        let stroke = if column % 5 == 0 { 2 } else { 1 };
+        let x = left + column * cell_size;
+
+        writeln!(
+            output,
+            "<line x1=\"{x}\" y1=\"{}\" x2=\"{x}\" y2=\"{}\" stroke=\"black\" stroke-width=\"{}\"/>",
+            top,
+            height,
+            stroke,
+            x = x
+        )
+        .unwrap();
+    }
+
+    output.push_str("</svg>\n");
+    output
+}
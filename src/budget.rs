@@ -0,0 +1,147 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+use std::time::Instant;
+
+/// A flag shared between the caller and a running solver, checked between propagation passes.
+///
+/// Clones share the same flag, so one can be handed to the solver while another cancels it from
+/// a different thread.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Asks every solver holding this token to stop at its next check.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Settings for `Nonogram::solve_with_options`.
+///
+/// Every limit is off by default. A solver that runs into any of them stops with
+/// `SolveStatus::BudgetExceeded` and whatever cells it had determined so far.
+#[derive(Clone, Debug, Default)]
+pub struct SolveOptions {
+    /// Wall time allowed for the whole solve, checked between lines while enumerating and
+    /// between passes. Needs a clock, so it is only available with the `std` feature, and on
+    /// `wasm32` only takes effect with the `wasm` feature, which reads the JavaScript clock.
+    #[cfg(feature = "std")]
+    pub max_time: Option<Duration>,
+    /// Number of propagation passes over every row and column.
+    pub max_passes: Option<usize>,
+    /// Number of candidate placements any single line may enumerate, which bounds memory use.
+    pub max_line_candidates: Option<usize>,
+    pub cancel: Option<CancelToken>,
+    /// Enumerate and propagate lines on the rayon thread pool.
+    #[cfg(feature = "parallel")]
+    pub parallel: bool,
+}
+
+/// The moment a solve started, by whichever clock the target has.
+///
+/// `Instant::now` panics on `wasm32-unknown-unknown`, so there the `wasm` feature reads
+/// `Date.now` from JavaScript instead. Without any clock no time ever passes.
+#[derive(Clone, Copy, Debug)]
+struct Clock(
+    #[cfg(all(feature = "std", not(target_arch = "wasm32")))] Instant,
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))] f64,
+);
+
+impl Clock {
+    fn now() -> Clock {
+        Clock(
+            #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+            Instant::now(),
+            #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+            js_sys::Date::now(),
+        )
+    }
+
+    fn elapsed(&self) -> Duration {
+        #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+        return self.0.elapsed();
+
+        #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+        return Duration::from_secs_f64((js_sys::Date::now() - self.0).max(0.0) / 1000.0);
+
+        #[cfg(not(any(
+            all(feature = "std", not(target_arch = "wasm32")),
+            all(feature = "wasm", target_arch = "wasm32")
+        )))]
+        Duration::from_secs(0)
+    }
+}
+
+/// Tracks a single solve against its `SolveOptions`.
+pub(crate) struct Budget<'a> {
+    options: &'a SolveOptions,
+    started: Clock,
+}
+
+impl<'a> Budget<'a> {
+    pub(crate) fn start(options: &'a SolveOptions) -> Budget<'a> {
+        Budget {
+            options,
+            started: Clock::now(),
+        }
+    }
+
+    pub(crate) fn line_candidates(&self) -> Option<usize> {
+        self.options.max_line_candidates
+    }
+
+    /// Time since the solve started, or zero without a clock.
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Whether the solve ran out of time or was cancelled.
+    pub(crate) fn interrupted(&self) -> bool {
+        let cancelled = self
+            .options
+            .cancel
+            .as_ref()
+            .is_some_and(CancelToken::is_cancelled);
+        #[cfg(feature = "std")]
+        let timed_out = self
+            .options
+            .max_time
+            .is_some_and(|max_time| self.elapsed() >= max_time);
+        #[cfg(not(feature = "std"))]
+        let timed_out = false;
+
+        cancelled || timed_out
+    }
+
+    /// The limits left for a follow-up solve once this one has used up `passes`, so that several
+    /// solves in a row can share one budget.
+    pub(crate) fn remaining(&self, passes: usize) -> SolveOptions {
+        SolveOptions {
+            #[cfg(feature = "std")]
+            max_time: self
+                .options
+                .max_time
+                .map(|max_time| max_time.saturating_sub(self.elapsed())),
+            max_passes: self
+                .options
+                .max_passes
+                .map(|max| max.saturating_sub(passes)),
+            ..self.options.clone()
+        }
+    }
+
+    /// Whether another pass may start after `passes` have run.
+    pub(crate) fn allows_pass(&self, passes: usize) -> bool {
+        !self.interrupted() && self.options.max_passes.map_or(true, |max| passes < max)
+    }
+}
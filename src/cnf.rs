@@ -0,0 +1,210 @@
+use super::prelude::*;
+use super::{Nonogram, NonogramError};
+use core::fmt::Write;
+use ndarray::Array2;
+
+/// A formula in conjunctive normal form, as DIMACS numbers it.
+///
+/// Variables run from 1 to `variables`, and a negative literal is the negation of its variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cnf {
+    pub variables: usize,
+    pub clauses: Vec<Vec<i64>>,
+}
+
+impl Cnf {
+    fn variable(&mut self) -> i64 {
+        self.variables += 1;
+        self.variables as i64
+    }
+
+    /// Rules out one picture, so a second solver run finds another solution if there is one.
+    ///
+    /// Only works on formulas from `Nonogram::to_cnf`, whose first variables are the cells.
+    pub fn exclude(&mut self, grid: &Array2<u8>) {
+        let clause = grid
+            .iter()
+            .enumerate()
+            .map(|(index, &cell)| {
+                let variable = index as i64 + 1;
+
+                if cell == 1 {
+                    -variable
+                } else {
+                    variable
+                }
+            })
+            .collect();
+
+        self.clauses.push(clause);
+    }
+
+    /// The formula in DIMACS CNF format, ready for any SAT solver.
+    pub fn to_dimacs(&self) -> String {
+        let mut dimacs = format!("p cnf {} {}\n", self.variables, self.clauses.len());
+
+        for clause in &self.clauses {
+            for literal in clause {
+                write!(dimacs, "{} ", literal).unwrap();
+            }
+
+            dimacs.push_str("0\n");
+        }
+
+        dimacs
+    }
+}
+
+/// Adds the clauses that make `cells` spell out `clue`.
+///
+/// Each block gets a variable for every place it could start. Exactly one of them holds, the next
+/// block starts after a gap, and a cell is filled exactly when some block covers it. A clue too
+/// long for its line makes the formula unsatisfiable.
+fn encode_line(cnf: &mut Cnf, clue: &[usize], cells: &[i64]) {
+    let n = cells.len();
+    let needed = clue.iter().sum::<usize>() + clue.len().saturating_sub(1);
+
+    if needed > n {
+        let contradiction = cnf.variable();
+
+        cnf.clauses.push(vec![contradiction]);
+        cnf.clauses.push(vec![-contradiction]);
+        return;
+    }
+
+    let mut covering: Vec<Vec<i64>> = vec![Vec::new(); n];
+    let mut starts: Vec<Vec<(usize, i64)>> = Vec::with_capacity(clue.len());
+    let mut earliest = 0;
+    let mut latest = n - needed;
+
+    for &length in clue {
+        let block: Vec<(usize, i64)> = (earliest..=latest)
+            .map(|start| (start, cnf.variable()))
+            .collect();
+
+        earliest += length + 1;
+        latest += length + 1;
+        starts.push(block);
+    }
+
+    for (k, block) in starts.iter().enumerate() {
+        cnf.clauses
+            .push(block.iter().map(|&(_, variable)| variable).collect());
+
+        for (index, &(_, first)) in block.iter().enumerate() {
+            for &(_, second) in &block[index + 1..] {
+                cnf.clauses.push(vec![-first, -second]);
+            }
+        }
+
+        for &(start, variable) in block {
+            for (cell, covers) in covering.iter_mut().enumerate().skip(start).take(clue[k]) {
+                cnf.clauses.push(vec![-variable, cells[cell]]);
+                covers.push(variable);
+            }
+
+            if let Some(next) = starts.get(k + 1) {
+                let mut clause = vec![-variable];
+
+                clause.extend(
+                    next.iter()
+                        .filter(|&&(later, _)| later > start + clue[k])
+                        .map(|&(_, later)| later),
+                );
+                cnf.clauses.push(clause);
+            }
+        }
+    }
+
+    for (cell, covers) in covering.into_iter().enumerate() {
+        let mut clause = vec![-cells[cell]];
+
+        clause.extend(covers);
+        cnf.clauses.push(clause);
+    }
+}
+
+impl Nonogram {
+    /// Encodes the clues as a SAT problem whose models are exactly the puzzle's solutions.
+    ///
+    /// Variable `row * width + column + 1` is true when that cell is filled. The variables after
+    /// the cells mark where each block of each line starts.
+    pub fn to_cnf(&self) -> Cnf {
+        let width = self.width() as i64;
+        let mut cnf = Cnf {
+            variables: self.height() * self.width(),
+            clauses: Vec::new(),
+        };
+
+        for (i, clue) in self.row_segments.iter().enumerate() {
+            let cells: Vec<i64> = (0..width).map(|j| i as i64 * width + j + 1).collect();
+
+            encode_line(&mut cnf, clue, &cells);
+        }
+
+        for (j, clue) in self.column_segments.iter().enumerate() {
+            let cells: Vec<i64> = (0..self.height() as i64)
+                .map(|i| i * width + j as i64 + 1)
+                .collect();
+
+            encode_line(&mut cnf, clue, &cells);
+        }
+
+        cnf
+    }
+
+    /// `to_cnf` in DIMACS format.
+    pub fn to_dimacs(&self) -> String {
+        self.to_cnf().to_dimacs()
+    }
+
+    /// Reads a SAT solver's answer to `to_cnf` back into a picture of 0s and 1s.
+    ///
+    /// Accepts the SAT competition output format, with an `s` status line and `v` lines of
+    /// literals, as well as bare lists of literals. Comment lines starting with `c` are skipped.
+    /// Returns `Ok(None)` when the solver reports the formula unsatisfiable.
+    pub fn grid_from_model(&self, model: &str) -> Result<Option<Array2<u8>>, NonogramError> {
+        let cells = self.height() * self.width();
+        let mut values: Vec<Option<u8>> = vec![None; cells];
+
+        for line in model.lines().map(str::trim) {
+            if line.starts_with('c') || line.is_empty() {
+                continue;
+            }
+
+            if line.contains("UNSAT") {
+                return Ok(None);
+            }
+
+            if line.starts_with('s') || line == "SAT" {
+                continue;
+            }
+
+            for token in line.trim_start_matches('v').split_whitespace() {
+                let literal: i64 = token.parse().map_err(|_| {
+                    NonogramError::InvalidModel(format!("`{}` is not a literal", token))
+                })?;
+                let variable = literal.unsigned_abs() as usize;
+
+                if (1..=cells).contains(&variable) {
+                    values[variable - 1] = Some((literal > 0) as u8);
+                }
+            }
+        }
+
+        let values = values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| {
+                value.ok_or_else(|| {
+                    NonogramError::InvalidModel(format!("no value for variable {}", index + 1))
+                })
+            })
+            .collect::<Result<Vec<u8>, NonogramError>>()?;
+
+        Ok(Some(Array2::from_shape_vec(
+            (self.height(), self.width()),
+            values,
+        )?))
+    }
+}
@@ -0,0 +1,127 @@
+use super::prelude::*;
+use super::{Nonogram, NonogramError};
+use ndarray::Array2;
+
+/// URL-safe base64 digits, each standing for six cells.
+const DIGITS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// The longest side a code may declare, so a few characters can't ask for a huge puzzle.
+const MAX_SIDE: usize = 1024;
+
+fn invalid(reason: String) -> NonogramError {
+    NonogramError::InvalidCode(reason)
+}
+
+impl Nonogram {
+    /// A short text code for the puzzle's picture, for links and copy and paste.
+    ///
+    /// The code is the size, as `{width}x{height}:`, followed by the cells row by row, six to
+    /// a URL-safe base64 digit with the first cell in the highest bit. Clue-only puzzles have no
+    /// picture to pack and give `None`.
+    pub fn as_code(&self) -> Option<String> {
+        if self.is_clue_only() {
+            return None;
+        }
+
+        let cells: Vec<u8> = self.completed_grid.iter().cloned().collect();
+        let digits: String = cells
+            .chunks(6)
+            .map(|chunk| {
+                let bits = (0..6).fold(0, |bits, index| {
+                    bits << 1 | chunk.get(index).map_or(0, |&cell| cell as usize)
+                });
+
+                DIGITS[bits] as char
+            })
+            .collect();
+
+        Some(format!("{}x{}:{}", self.width(), self.height(), digits))
+    }
+
+    /// Reads a code written by `as_code` and derives the clues from its picture.
+    ///
+    /// Codes come from users, so anything malformed is an `InvalidCode` error rather than a
+    /// panic, including sizes with a side longer than 1024 cells.
+    pub fn from_code(code: &str) -> Result<Nonogram, NonogramError> {
+        let (size, digits) = code
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| invalid("missing `:` after the size".to_string()))?;
+        let (width, height): (usize, usize) = size
+            .split_once('x')
+            .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+            .ok_or_else(|| invalid(format!("`{}` is not a size like `10x5`", size)))?;
+        let count = width
+            .checked_mul(height)
+            .filter(|_| width <= MAX_SIDE && height <= MAX_SIDE)
+            .ok_or_else(|| invalid(format!("{} is larger than {}x{}", size, MAX_SIDE, MAX_SIDE)))?;
+
+        if digits.len() != count.div_ceil(6) {
+            return Err(invalid(format!(
+                "{} digits cannot hold {} cells",
+                digits.len(),
+                count
+            )));
+        }
+
+        let mut cells = Vec::with_capacity(digits.len() * 6);
+
+        for digit in digits.bytes() {
+            let bits = DIGITS
+                .iter()
+                .position(|&d| d == digit)
+                .ok_or_else(|| invalid(format!("`{}` is not a code digit", digit as char)))?;
+
+            cells.extend((0..6).rev().map(|shift| (bits >> shift & 1) as u8));
+        }
+
+        if cells[count..].contains(&1) {
+            return Err(invalid("cells past the end of the grid".to_string()));
+        }
+
+        cells.truncate(count);
+
+        Ok(Nonogram::from_grid(Array2::from_shape_vec(
+            (height, width),
+            cells,
+        )?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{arr2, Nonogram, NonogramError};
+
+    #[test]
+    fn packs_six_cells_per_digit() {
+        let puzzle = Nonogram::from_grid(arr2(&[[1, 0, 0, 0], [0, 0, 0, 1]]));
+
+        // 100000 is 32, `g`; then 01 padded to 010000 is 16, `Q`.
+        assert_eq!(puzzle.as_code().unwrap(), "4x2:gQ");
+        assert_eq!(Nonogram::from_code("4x2:gQ").unwrap(), puzzle);
+    }
+
+    #[test]
+    fn rejects_bad_codes() {
+        for code in &["4x2gQ", "4y2:gQ", "4x2:g", "4x2:g!", "4x2:gR"] {
+            assert!(Nonogram::from_code(code).is_err(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn rejects_oversized_headers() {
+        for code in &[
+            "4294967296x4294967296:",
+            "18446744073709551615x2:A",
+            "1025x0:",
+            "0x4294967296:",
+        ] {
+            match Nonogram::from_code(code) {
+                Err(NonogramError::InvalidCode(_)) => (),
+                result => panic!("{}: {:?}", code, result),
+            }
+        }
+
+        assert_eq!(Nonogram::from_code("1024x0:").unwrap().width(), 1024);
+    }
+}
@@ -0,0 +1,753 @@
+use super::prelude::*;
+#[cfg(feature = "std")]
+use super::GenerateOptions;
+use super::{Line, NonogramError, SolveStatus};
+use crc::crc64::checksum_ecma;
+use ndarray::{Array1, Array2, ArrayView1, ErrorKind, ShapeError};
+#[cfg(feature = "std")]
+use rand::distributions::Bernoulli;
+#[cfg(feature = "std")]
+use rand::Rng;
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The largest palette a coloured puzzle can use, background included.
+pub const MAX_COLORS: usize = 64;
+
+/// One segment of a coloured clue: `length` consecutive cells of palette colour `color`.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash, Serialize, Deserialize)]
+pub struct ColorSegment {
+    pub length: usize,
+    pub color: u8,
+}
+
+/// A nonogram whose cells can take any colour from a palette.
+///
+/// `palette[0]` is the background colour and every other entry is a colour that clue segments
+/// can use. Cells of `completed_grid` hold indexes into the palette. Two neighbouring segments
+/// of the same colour must be separated by at least one background cell, while segments of
+/// different colours may touch.
+///
+/// As with `Nonogram`, a puzzle made with `from_clues` has an empty completed grid.
+#[derive(Debug, Clone)]
+pub struct ColorNonogram {
+    pub palette: Vec<[u8; 3]>,
+    pub row_segments: Array1<Vec<ColorSegment>>,
+    pub column_segments: Array1<Vec<ColorSegment>>,
+    pub completed_grid: Array2<u8>,
+}
+
+/// The outcome of running the solver on a coloured puzzle.
+#[derive(Clone, Debug)]
+pub struct ColorSolveResult {
+    pub status: SolveStatus,
+    /// The palette index of every cell the solver determined, or `None` where it could not.
+    pub grid: Array2<Option<u8>>,
+}
+
+fn build_color_clue(line: ArrayView1<u8>) -> Vec<ColorSegment> {
+    let mut clue: Vec<ColorSegment> = Vec::new();
+    let mut previous = 0;
+
+    for &cell in line.iter() {
+        if cell != 0 {
+            match clue.last_mut() {
+                Some(segment) if previous == cell => segment.length += 1,
+                _ => clue.push(ColorSegment {
+                    length: 1,
+                    color: cell,
+                }),
+            }
+        }
+
+        previous = cell;
+    }
+
+    clue
+}
+
+/// The fewest cells a line needs to hold `clue`, counting the gaps between same-coloured segments.
+fn min_length(clue: &[ColorSegment]) -> usize {
+    let gaps = clue
+        .windows(2)
+        .filter(|pair| pair[0].color == pair[1].color)
+        .count();
+
+    clue.iter().map(|segment| segment.length).sum::<usize>() + gaps
+}
+
+/// Narrows the candidate colours of each cell in a line down to those used by at least one
+/// placement of `clue`. Each cell is a bitmask with bit `n` set if palette colour `n` is still
+/// possible. Returns `None` if no placement fits.
+fn solve_line(clue: &[ColorSegment], cells: &[u64]) -> Option<Vec<u64>> {
+    let n = cells.len();
+    let k = clue.len();
+    let background = |p: usize| cells[p] & 1 != 0;
+    let gap_after = |i: usize| i + 1 < k && clue[i].color == clue[i + 1].color;
+    let covers = |i: usize, p: usize| {
+        let end = p + clue[i].length;
+        let bit = 1u64 << clue[i].color;
+
+        end <= n && cells[p..end].iter().all(|&cell| cell & bit != 0)
+    };
+
+    // fits[i][p]: segments i.. can be placed in cells p..
+    let mut fits = vec![vec![false; n + 1]; k + 1];
+
+    fits[k][n] = true;
+    for p in (0..n).rev() {
+        fits[k][p] = background(p) && fits[k][p + 1];
+    }
+
+    for i in (0..k).rev() {
+        for p in (0..=n).rev() {
+            let skip = p < n && background(p) && fits[i][p + 1];
+            let place = covers(i, p) && {
+                let end = p + clue[i].length;
+
+                if gap_after(i) {
+                    end < n && background(end) && fits[i + 1][end + 1]
+                } else {
+                    fits[i + 1][end]
+                }
+            };
+
+            fits[i][p] = skip || place;
+        }
+    }
+
+    if !fits[0][0] {
+        return None;
+    }
+
+    let mut reached = vec![vec![false; n + 1]; k + 1];
+    let mut narrowed = vec![0u64; n];
+
+    reached[0][0] = true;
+
+    for i in 0..=k {
+        for p in 0..=n {
+            if !reached[i][p] || !fits[i][p] {
+                continue;
+            }
+
+            if i == k {
+                narrowed[p..].iter_mut().for_each(|cell| *cell |= 1);
+                continue;
+            }
+
+            if p < n && background(p) && fits[i][p + 1] {
+                narrowed[p] |= 1;
+                reached[i][p + 1] = true;
+            }
+
+            if covers(i, p) {
+                let end = p + clue[i].length;
+
+                if gap_after(i) {
+                    if end < n && background(end) && fits[i + 1][end + 1] {
+                        narrowed[p..end]
+                            .iter_mut()
+                            .for_each(|cell| *cell |= 1 << clue[i].color);
+                        narrowed[end] |= 1;
+                        reached[i + 1][end + 1] = true;
+                    }
+                } else if fits[i + 1][end] {
+                    narrowed[p..end]
+                        .iter_mut()
+                        .for_each(|cell| *cell |= 1 << clue[i].color);
+                    reached[i + 1][end] = true;
+                }
+            }
+        }
+    }
+
+    Some(narrowed)
+}
+
+impl ColorNonogram {
+    /// Generates a random coloured nonogram.
+    ///
+    /// `options.density` is the chance of a cell not being background, and such cells pick one of
+    /// the palette's other colours uniformly. Returns `None` if `require_unique` is set and no
+    /// uniquely solvable grid turned up within `max_attempts` tries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the palette has fewer than two or more than `MAX_COLORS` entries.
+    #[cfg(feature = "std")]
+    pub fn generate_with(
+        options: &GenerateOptions,
+        palette: Vec<[u8; 3]>,
+    ) -> Option<ColorNonogram> {
+        assert!(
+            palette.len() >= 2 && palette.len() <= MAX_COLORS,
+            "palette needs between 2 and {} colours",
+            MAX_COLORS
+        );
+
+        let mut rng = options.rng();
+        let colored = Bernoulli::new(options.fill_probability());
+        let colors = palette.len() as u8;
+
+        for _ in 0..options.max_attempts.max(1) {
+            let grid = Array2::from_shape_fn((options.height, options.width), |_| {
+                if rng.sample(colored) {
+                    rng.gen_range(1, colors)
+                } else {
+                    0
+                }
+            });
+            let nonogram = ColorNonogram::from_grid(palette.clone(), grid)
+                .expect("generated cells are within the palette");
+
+            if !options.require_unique || nonogram.solvable() {
+                return Some(nonogram);
+            }
+        }
+
+        None
+    }
+
+    /// Builds a coloured nonogram from a picture of palette indexes, deriving its clues.
+    pub fn from_grid(
+        palette: Vec<[u8; 3]>,
+        completed_grid: Array2<u8>,
+    ) -> Result<ColorNonogram, NonogramError> {
+        let nonogram = ColorNonogram {
+            palette,
+            row_segments: completed_grid
+                .rows()
+                .into_iter()
+                .map(build_color_clue)
+                .collect(),
+            column_segments: completed_grid
+                .columns()
+                .into_iter()
+                .map(build_color_clue)
+                .collect(),
+            completed_grid,
+        };
+
+        nonogram.validate()?;
+
+        Ok(nonogram)
+    }
+
+    /// Builds a coloured puzzle from its clues alone, for when only the clues are known. The
+    /// completed grid is left empty and the clues aren't checked, see `validate`.
+    pub fn from_clues(
+        palette: Vec<[u8; 3]>,
+        row_segments: Array1<Vec<ColorSegment>>,
+        column_segments: Array1<Vec<ColorSegment>>,
+    ) -> ColorNonogram {
+        ColorNonogram {
+            palette,
+            row_segments,
+            column_segments,
+            completed_grid: Array2::zeros((0, 0)),
+        }
+    }
+
+    /// Whether the puzzle only has clues, with an empty completed grid.
+    pub fn is_clue_only(&self) -> bool {
+        self.completed_grid.dim() == (0, 0) && (self.height(), self.width()) != (0, 0)
+    }
+
+    /// Provides the height of the puzzle, which is its number of row clues.
+    pub fn height(&self) -> usize {
+        self.row_segments.len()
+    }
+
+    /// Provides the width of the puzzle, which is its number of column clues.
+    pub fn width(&self) -> usize {
+        self.column_segments.len()
+    }
+
+    /// The first clue segment whose colour is the background or outside the palette.
+    fn invalid_clue_color(&self) -> Option<(Line, u8)> {
+        let colors = self.palette.len().min(MAX_COLORS);
+        let lines = self
+            .row_segments
+            .iter()
+            .enumerate()
+            .map(|(i, clue)| (Line::Row(i), clue))
+            .chain(
+                self.column_segments
+                    .iter()
+                    .enumerate()
+                    .map(|(j, clue)| (Line::Column(j), clue)),
+            );
+
+        lines
+            .flat_map(|(line, clue)| clue.iter().map(move |segment| (line, segment.color)))
+            .find(|&(_, color)| color == 0 || color as usize >= colors)
+    }
+
+    /// Checks the palette size, that every clue segment uses a palette colour other than the
+    /// background and fits its line, that every cell refers to a palette colour and that every
+    /// clue describes its line of the completed grid.
+    ///
+    /// Clue-only puzzles just have their palette and clues checked.
+    pub fn validate(&self) -> Result<(), NonogramError> {
+        if self.palette.is_empty() || self.palette.len() > MAX_COLORS {
+            return Err(NonogramError::InvalidPalette(self.palette.len()));
+        }
+
+        if let Some((line, color)) = self.invalid_clue_color() {
+            return Err(NonogramError::InvalidClueColor { line, color });
+        }
+
+        if let Some(i) =
+            (0..self.height()).find(|&i| min_length(&self.row_segments[i]) > self.width())
+        {
+            return Err(NonogramError::ClueMismatch(Line::Row(i)));
+        }
+
+        if let Some(j) =
+            (0..self.width()).find(|&j| min_length(&self.column_segments[j]) > self.height())
+        {
+            return Err(NonogramError::ClueMismatch(Line::Column(j)));
+        }
+
+        if self.is_clue_only() {
+            return Ok(());
+        }
+
+        if let Some(((row, column), &value)) = self
+            .completed_grid
+            .indexed_iter()
+            .find(|(_, &cell)| cell as usize >= self.palette.len())
+        {
+            return Err(NonogramError::InvalidCell { row, column, value });
+        }
+
+        if self.completed_grid.dim() != (self.height(), self.width()) {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape).into());
+        }
+
+        for (i, row) in self.completed_grid.rows().into_iter().enumerate() {
+            if build_color_clue(row) != self.row_segments[i] {
+                return Err(NonogramError::ClueMismatch(Line::Row(i)));
+            }
+        }
+
+        for (j, column) in self.completed_grid.columns().into_iter().enumerate() {
+            if build_color_clue(column) != self.column_segments[j] {
+                return Err(NonogramError::ClueMismatch(Line::Column(j)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Determines whether the solver can work out every cell from the clues alone.
+    pub fn solvable(&self) -> bool {
+        self.solve().status == SolveStatus::Solved
+    }
+
+    /// Runs the solver and reports how far it got, along with every cell it could determine.
+    ///
+    /// Each line is narrowed to the colours that appear in at least one placement of its clue
+    /// consistent with what is already known, alternating rows and columns until nothing changes.
+    ///
+    /// A clue in the background colour or outside the palette, or an unsupported palette, makes
+    /// the puzzle a contradiction.
+    pub fn solve(&self) -> ColorSolveResult {
+        if self.palette.is_empty()
+            || self.palette.len() > MAX_COLORS
+            || self.invalid_clue_color().is_some()
+        {
+            return ColorSolveResult {
+                status: SolveStatus::Contradiction,
+                grid: Array2::from_elem((self.height(), self.width()), None),
+            };
+        }
+
+        let all_colors = if self.palette.len() == MAX_COLORS {
+            u64::MAX
+        } else {
+            (1u64 << self.palette.len()) - 1
+        };
+        let mut grid: Array2<u64> = Array2::from_elem((self.height(), self.width()), all_colors);
+
+        let status = loop {
+            let mut changes = 0;
+            let mut contradiction = false;
+
+            for (index, clue) in self.row_segments.iter().enumerate() {
+                let line: Vec<u64> = grid.row(index).iter().cloned().collect();
+
+                match solve_line(clue, &line) {
+                    Some(narrowed) => {
+                        for (cell, new) in grid.row_mut(index).iter_mut().zip(narrowed) {
+                            if *cell != new {
+                                *cell = new;
+                                changes += 1;
+                            }
+                        }
+                    }
+                    None => contradiction = true,
+                }
+            }
+
+            for (index, clue) in self.column_segments.iter().enumerate() {
+                let line: Vec<u64> = grid.column(index).iter().cloned().collect();
+
+                match solve_line(clue, &line) {
+                    Some(narrowed) => {
+                        for (cell, new) in grid.column_mut(index).iter_mut().zip(narrowed) {
+                            if *cell != new {
+                                *cell = new;
+                                changes += 1;
+                            }
+                        }
+                    }
+                    None => contradiction = true,
+                }
+            }
+
+            if contradiction {
+                break SolveStatus::Contradiction;
+            }
+
+            // Narrowing the columns can settle every cell into a grid that breaks a row clue, so
+            // the finished grid still has to match every clue.
+            if grid.iter().all(|cell| cell.count_ones() == 1) {
+                let colors = grid.map(|cell| cell.trailing_zeros() as u8);
+                let fits = colors
+                    .rows()
+                    .into_iter()
+                    .zip(self.row_segments.iter())
+                    .chain(
+                        colors
+                            .columns()
+                            .into_iter()
+                            .zip(self.column_segments.iter()),
+                    )
+                    .all(|(line, clue)| build_color_clue(line) == *clue);
+
+                break if fits {
+                    SolveStatus::Solved
+                } else {
+                    SolveStatus::Contradiction
+                };
+            }
+
+            if changes == 0 {
+                break SolveStatus::Stalled;
+            }
+        };
+
+        ColorSolveResult {
+            status,
+            grid: grid.map(|cell| {
+                if cell.count_ones() == 1 {
+                    Some(cell.trailing_zeros() as u8)
+                } else {
+                    None
+                }
+            }),
+        }
+    }
+
+    /// A checksum over the palette and the completed grid, in the spirit of
+    /// `Nonogram::generate_checksum`.
+    pub fn generate_checksum(&self) -> u64 {
+        let mut aggregate: Vec<u8> = self.palette.iter().flatten().cloned().collect();
+
+        aggregate.extend(self.completed_grid.iter());
+
+        checksum_ecma(aggregate.as_slice())
+    }
+
+    /// Serializes the coloured nonogram as json.
+    #[cfg(feature = "std")]
+    pub fn as_json(&self) -> Result<String, NonogramError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Deserializes a coloured nonogram from json, validating it against its stored checksum.
+    #[cfg(feature = "std")]
+    pub fn from_json(serialized: &str) -> Result<ColorNonogram, NonogramError> {
+        serde_json::from_str::<SerializedColorNonogram>(serialized)?.to_nonogram()
+    }
+}
+
+impl Serialize for ColorNonogram {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        SerializedColorNonogram {
+            checksum: self.generate_checksum(),
+            height: self.height(),
+            width: self.width(),
+            palette: self.palette.clone(),
+            row_segments: self.row_segments.iter().cloned().collect(),
+            column_segments: self.column_segments.iter().cloned().collect(),
+            completed_grid: self
+                .completed_grid
+                .rows()
+                .into_iter()
+                .map(|row| row.iter().cloned().collect())
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ColorNonogram {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        SerializedColorNonogram::deserialize(deserializer)?
+            .to_nonogram()
+            .map_err(D::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedColorNonogram {
+    #[serde(with = "crate::checksum_format")]
+    checksum: u64,
+    height: usize,
+    width: usize,
+    palette: Vec<[u8; 3]>,
+    row_segments: Vec<Vec<ColorSegment>>,
+    column_segments: Vec<Vec<ColorSegment>>,
+    completed_grid: Vec<Vec<u8>>,
+}
+
+impl SerializedColorNonogram {
+    fn to_nonogram(&self) -> Result<ColorNonogram, NonogramError> {
+        let clue_only = self.completed_grid.is_empty()
+            && self.row_segments.len() == self.height
+            && self.column_segments.len() == self.width;
+        let completed_grid = if clue_only {
+            Array2::zeros((0, 0))
+        } else {
+            Array2::from_shape_vec(
+                (self.height, self.width),
+                self.completed_grid.iter().flatten().cloned().collect(),
+            )?
+        };
+
+        let nonogram = ColorNonogram {
+            palette: self.palette.clone(),
+            row_segments: self.row_segments.iter().cloned().collect(),
+            column_segments: self.column_segments.iter().cloned().collect(),
+            completed_grid,
+        };
+
+        nonogram.validate()?;
+
+        let computed = nonogram.generate_checksum();
+
+        if computed != self.checksum {
+            return Err(NonogramError::ChecksumMismatch {
+                stored: self.checksum,
+                computed,
+            });
+        }
+
+        Ok(nonogram)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_color_clue, solve_line, ColorNonogram, ColorSegment};
+    #[cfg(feature = "std")]
+    use crate::GenerateOptions;
+    use crate::{Line, NonogramError, SolveStatus};
+    use ndarray::{arr1, arr2};
+
+    const PALETTE: [[u8; 3]; 3] = [[255, 255, 255], [255, 0, 0], [0, 0, 255]];
+
+    fn segment(length: usize, color: u8) -> ColorSegment {
+        ColorSegment { length, color }
+    }
+
+    #[test]
+    fn clue_splits_on_colour_changes() {
+        let clue = build_color_clue(arr1(&[1, 1, 2, 0, 2, 2, 0, 1]).view());
+
+        assert_eq!(
+            clue,
+            vec![segment(2, 1), segment(1, 2), segment(2, 2), segment(1, 1)]
+        );
+    }
+
+    #[test]
+    fn line_overlap_for_single_colour() {
+        let narrowed = solve_line(&[segment(3, 1)], &[0b111; 5]).unwrap();
+
+        assert_eq!(narrowed, vec![0b011, 0b011, 0b010, 0b011, 0b011]);
+    }
+
+    #[test]
+    fn line_different_colours_may_touch() {
+        let narrowed = solve_line(&[segment(2, 1), segment(1, 2)], &[0b111; 3]).unwrap();
+
+        assert_eq!(narrowed, vec![0b010, 0b010, 0b100]);
+    }
+
+    #[test]
+    fn line_same_colours_need_a_gap() {
+        let narrowed = solve_line(&[segment(2, 1), segment(1, 1)], &[0b111; 4]).unwrap();
+
+        assert_eq!(narrowed, vec![0b010, 0b010, 0b001, 0b010]);
+        assert!(solve_line(&[segment(2, 1), segment(1, 1)], &[0b111; 3]).is_none());
+    }
+
+    #[test]
+    fn solves_coloured_puzzle() {
+        let puzzle =
+            ColorNonogram::from_grid(PALETTE.to_vec(), arr2(&[[1, 1, 2], [0, 2, 2], [1, 0, 2]]))
+                .unwrap();
+
+        let result = puzzle.solve();
+
+        assert_eq!(result.status, SolveStatus::Solved);
+        assert_eq!(result.grid[[0, 2]], Some(2));
+        assert_eq!(result.grid[[1, 0]], Some(0));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn seeded_generation_round_trips_through_json() {
+        let mut options = GenerateOptions::new(6, 4);
+        options.seed = Some(5);
+
+        let puzzle = ColorNonogram::generate_with(&options, PALETTE.to_vec()).unwrap();
+        let deserialized = ColorNonogram::from_json(&puzzle.as_json().unwrap()).unwrap();
+
+        assert!(puzzle.completed_grid.iter().all(|&cell| cell < 3));
+        assert_eq!(deserialized.completed_grid, puzzle.completed_grid);
+        assert_eq!(deserialized.row_segments, puzzle.row_segments);
+        assert_eq!(deserialized.palette, puzzle.palette);
+    }
+
+    #[test]
+    fn validate_rejects_cells_outside_palette() {
+        let mut puzzle =
+            ColorNonogram::from_grid(PALETTE.to_vec(), arr2(&[[1, 0], [0, 2]])).unwrap();
+
+        puzzle.completed_grid[[0, 1]] = 3;
+
+        match puzzle.validate().unwrap_err() {
+            NonogramError::InvalidCell { value, .. } => assert_eq!(value, 3),
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn validate_rejects_wrong_colour_in_clue() {
+        let mut puzzle =
+            ColorNonogram::from_grid(PALETTE.to_vec(), arr2(&[[1, 0], [0, 2]])).unwrap();
+
+        puzzle.row_segments[1] = vec![segment(1, 1)];
+
+        match puzzle.validate().unwrap_err() {
+            NonogramError::ClueMismatch(line) => assert_eq!(line, Line::Row(1)),
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn validate_rejects_missing_clues() {
+        let mut puzzle =
+            ColorNonogram::from_grid(PALETTE.to_vec(), arr2(&[[1, 0], [0, 2]])).unwrap();
+
+        puzzle.column_segments = arr1(&[vec![segment(1, 1)]]);
+
+        match puzzle.validate().unwrap_err() {
+            NonogramError::ShapeMismatch(_) => (),
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn solve_checks_the_finished_grid_against_every_clue() {
+        // The rows allow one cell in total and the columns need two. Once the columns are
+        // narrowed every cell is settled, but the top row holds two cells.
+        let puzzle = ColorNonogram {
+            palette: PALETTE[..2].to_vec(),
+            row_segments: arr1(&[vec![segment(1, 1)], vec![]]),
+            column_segments: arr1(&[vec![segment(1, 1)], vec![segment(1, 1)]]),
+            completed_grid: arr2(&[[0, 0], [0, 0]]),
+        };
+
+        assert_eq!(puzzle.solve().status, SolveStatus::Contradiction);
+    }
+
+    #[test]
+    fn clue_colours_must_be_segment_colours_of_the_palette() {
+        for color in [0, 3, 64, 200] {
+            let mut puzzle =
+                ColorNonogram::from_grid(PALETTE.to_vec(), arr2(&[[1, 0], [0, 2]])).unwrap();
+
+            puzzle.column_segments[1] = vec![segment(1, color)];
+
+            let result = puzzle.solve();
+
+            assert_eq!(result.status, SolveStatus::Contradiction);
+            assert_eq!(result.grid.dim(), (2, 2));
+
+            match puzzle.validate().unwrap_err() {
+                NonogramError::InvalidClueColor { line, color: found } => {
+                    assert_eq!((line, found), (Line::Column(1), color))
+                }
+                e => panic!("unexpected error: {}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn clue_only_puzzles_take_their_size_from_the_clues() {
+        let puzzle = ColorNonogram::from_clues(
+            PALETTE.to_vec(),
+            arr1(&[vec![segment(2, 1), segment(1, 2)], vec![segment(1, 2)]]),
+            arr1(&[
+                vec![segment(1, 1)],
+                vec![segment(1, 1)],
+                vec![segment(2, 2)],
+            ]),
+        );
+
+        assert!(puzzle.is_clue_only());
+        assert_eq!((puzzle.height(), puzzle.width()), (2, 3));
+        assert!(puzzle.validate().is_ok());
+
+        let result = puzzle.solve();
+
+        assert_eq!(result.status, SolveStatus::Solved);
+        assert_eq!(result.grid, arr2(&[[1, 1, 2], [0, 0, 2]]).map(|&c| Some(c)));
+
+        let mut too_long = puzzle.clone();
+
+        too_long.row_segments[1] = vec![segment(1, 1), segment(1, 1), segment(1, 2)];
+
+        match too_long.validate().unwrap_err() {
+            NonogramError::ClueMismatch(line) => assert_eq!(line, Line::Row(1)),
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn clue_only_puzzles_round_trip_through_json() {
+        let puzzle = ColorNonogram::from_clues(
+            PALETTE.to_vec(),
+            arr1(&[vec![segment(1, 2)]]),
+            arr1(&[vec![], vec![segment(1, 2)]]),
+        );
+        let deserialized = ColorNonogram::from_json(&puzzle.as_json().unwrap()).unwrap();
+
+        assert!(deserialized.is_clue_only());
+        assert_eq!(deserialized.row_segments, puzzle.row_segments);
+        assert_eq!(deserialized.column_segments, puzzle.column_segments);
+    }
+}
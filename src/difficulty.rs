@@ -0,0 +1,112 @@
+use super::budget::Budget;
+use super::prelude::*;
+use super::search::{probe, search};
+use super::{
+    EdgeForcing, LineStrategy, Nonogram, SimpleOverlap, SimpleSpaces, SolveOptions, SolveStatus,
+};
+
+/// How much work the solver needs to finish a puzzle, from least to most.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    /// Simple overlaps and simple spaces are enough.
+    Trivial,
+    /// Needs edge forcing, reasoning from cells already known at the ends of blocks.
+    Easy,
+    /// Needs every placement of a line considered at once, but never more than one line.
+    Medium,
+    /// Line logic stalls, but trying a single cell and finding a contradiction gets it going.
+    Hard,
+    /// The solution is unique, but only deeper guessing and backtracking finds it.
+    Expert,
+    /// The clues have several solutions, or none.
+    Unsolvable,
+}
+
+impl Difficulty {
+    /// How many steps `self` is from the nearest difficulty in `band`.
+    #[cfg(feature = "std")]
+    pub(crate) fn distance_to(self, band: &core::ops::RangeInclusive<Difficulty>) -> usize {
+        if self < *band.start() {
+            *band.start() as usize - self as usize
+        } else if self > *band.end() {
+            self as usize - *band.end() as usize
+        } else {
+            0
+        }
+    }
+}
+
+impl Nonogram {
+    /// Rates the puzzle by the weakest technique that solves it.
+    ///
+    /// Tries each technique in turn, so easy puzzles are rated quickly while telling `Expert`
+    /// from `Unsolvable` means searching for a second solution, which can take a long time on
+    /// large puzzles. `difficulty_with_options` bounds that work.
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty_with_options(&SolveOptions::default())
+            .expect("an unlimited rating always finishes")
+    }
+
+    /// `difficulty`, giving up with `None` when the rating runs into any limit of `options` before
+    /// it is settled.
+    ///
+    /// The limits cover the whole rating: the solver, probing and the search for a second
+    /// solution share one budget, with `max_passes` counting the passes of all three.
+    pub fn difficulty_with_options(&self, options: &SolveOptions) -> Option<Difficulty> {
+        let simple: Vec<Box<dyn LineStrategy>> =
+            vec![Box::new(SimpleOverlap), Box::new(SimpleSpaces)];
+        let edges: Vec<Box<dyn LineStrategy>> = vec![
+            Box::new(SimpleOverlap),
+            Box::new(SimpleSpaces),
+            Box::new(EdgeForcing),
+        ];
+
+        if self.solve_with_strategies(&simple).result.status == SolveStatus::Solved {
+            return Some(Difficulty::Trivial);
+        }
+
+        if self.solve_with_strategies(&edges).result.status == SolveStatus::Solved {
+            return Some(Difficulty::Easy);
+        }
+
+        let budget = Budget::start(options);
+        let solved = self.solve_with_options(options);
+
+        match solved.status {
+            SolveStatus::Solved => return Some(Difficulty::Medium),
+            SolveStatus::Contradiction => return Some(Difficulty::Unsolvable),
+            SolveStatus::BudgetExceeded => return None,
+            SolveStatus::Stalled => (),
+        }
+
+        let (status, stats) = probe(self, &budget.remaining(solved.stats.passes));
+
+        match status {
+            SolveStatus::Solved => return Some(Difficulty::Hard),
+            SolveStatus::BudgetExceeded => return None,
+            _ => (),
+        }
+
+        let passes = solved.stats.passes + stats.passes;
+        let search = search(self, 2, &budget.remaining(passes));
+
+        match search.solutions.len() {
+            1 if search.complete => Some(Difficulty::Expert),
+            0 if search.complete => Some(Difficulty::Unsolvable),
+            found if found >= 2 => Some(Difficulty::Unsolvable),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::Difficulty::*;
+
+    #[test]
+    fn distance_to_band() {
+        assert_eq!(Trivial.distance_to(&(Hard..=Expert)), 3);
+        assert_eq!(Hard.distance_to(&(Hard..=Expert)), 0);
+        assert_eq!(Unsolvable.distance_to(&(Easy..=Medium)), 3);
+    }
+}
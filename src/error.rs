@@ -0,0 +1,162 @@
+use super::prelude::*;
+use core::error::Error;
+use core::fmt;
+use ndarray::ShapeError;
+#[cfg(feature = "std")]
+use serde_json::Error as JsonError;
+
+/// Identifies a single row or column of a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Line {
+    Row(usize),
+    Column(usize),
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Line::Row(index) => write!(f, "row {}", index),
+            Line::Column(index) => write!(f, "column {}", index),
+        }
+    }
+}
+
+/// Errors produced while loading or validating a nonogram.
+#[derive(Debug)]
+pub enum NonogramError {
+    /// The input was not valid JSON or did not have the expected fields.
+    #[cfg(feature = "std")]
+    Json(JsonError),
+    /// The nonogram could not be written as CBOR.
+    #[cfg(feature = "cbor")]
+    CborEncode(ciborium::ser::Error<std::io::Error>),
+    /// The input was not valid CBOR or did not have the expected fields.
+    #[cfg(feature = "cbor")]
+    CborDecode(ciborium::de::Error<std::io::Error>),
+    /// The nonogram could not be written as MessagePack.
+    #[cfg(feature = "msgpack")]
+    MessagePackEncode(rmp_serde::encode::Error),
+    /// The input was not valid MessagePack or did not have the expected fields.
+    #[cfg(feature = "msgpack")]
+    MessagePackDecode(rmp_serde::decode::Error),
+    /// Bincode failed to encode the nonogram or to decode the input.
+    #[cfg(feature = "bincode")]
+    Bincode(bincode::Error),
+    /// The grid data does not fit the declared height and width.
+    ShapeMismatch(ShapeError),
+    /// A clue does not describe the matching line of the completed grid.
+    ClueMismatch(Line),
+    /// The stored checksum differs from the one computed from the completed grid.
+    ChecksumMismatch { stored: u64, computed: u64 },
+    /// A cell of the completed grid holds something other than 0 or 1, or for coloured puzzles,
+    /// something outside the palette.
+    InvalidCell {
+        row: usize,
+        column: usize,
+        value: u8,
+    },
+    /// A SAT solver's output could not be read as a model of the puzzle's formula.
+    InvalidModel(String),
+    /// A puzzle code is malformed.
+    InvalidCode(String),
+    /// A coloured puzzle's palette is empty or has more than `MAX_COLORS` entries.
+    InvalidPalette(usize),
+    /// A coloured clue has a segment in the background colour or in a colour outside the palette.
+    InvalidClueColor { line: Line, color: u8 },
+}
+
+impl fmt::Display for NonogramError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            NonogramError::Json(e) => e.fmt(f),
+            #[cfg(feature = "cbor")]
+            NonogramError::CborEncode(e) => e.fmt(f),
+            #[cfg(feature = "cbor")]
+            NonogramError::CborDecode(e) => e.fmt(f),
+            #[cfg(feature = "msgpack")]
+            NonogramError::MessagePackEncode(e) => e.fmt(f),
+            #[cfg(feature = "msgpack")]
+            NonogramError::MessagePackDecode(e) => e.fmt(f),
+            #[cfg(feature = "bincode")]
+            NonogramError::Bincode(e) => e.fmt(f),
+            NonogramError::ShapeMismatch(e) => e.fmt(f),
+            NonogramError::ClueMismatch(line) => {
+                write!(f, "clue for {} does not match the completed grid", line)
+            }
+            NonogramError::ChecksumMismatch { stored, computed } => write!(
+                f,
+                "checksum mismatch: stored {} but the grid hashes to {}",
+                stored, computed
+            ),
+            NonogramError::InvalidCell { row, column, value } => write!(
+                f,
+                "invalid cell value {} at row {}, column {}",
+                value, row, column
+            ),
+            NonogramError::InvalidModel(reason) => write!(f, "invalid SAT model: {}", reason),
+            NonogramError::InvalidCode(reason) => write!(f, "invalid puzzle code: {}", reason),
+            NonogramError::InvalidPalette(size) => {
+                write!(f, "a palette of {} colours is not supported", size)
+            }
+            NonogramError::InvalidClueColor { line, color } => write!(
+                f,
+                "clue for {} has a segment in colour {}, which is not a segment colour",
+                line, color
+            ),
+        }
+    }
+}
+
+impl Error for NonogramError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            NonogramError::Json(e) => Some(e),
+            #[cfg(feature = "cbor")]
+            NonogramError::CborEncode(e) => Some(e),
+            #[cfg(feature = "cbor")]
+            NonogramError::CborDecode(e) => Some(e),
+            #[cfg(feature = "msgpack")]
+            NonogramError::MessagePackEncode(e) => Some(e),
+            #[cfg(feature = "msgpack")]
+            NonogramError::MessagePackDecode(e) => Some(e),
+            #[cfg(feature = "bincode")]
+            NonogramError::Bincode(e) => Some(e),
+            // ndarray only implements `Error` for its errors with std.
+            #[cfg(feature = "std")]
+            NonogramError::ShapeMismatch(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<JsonError> for NonogramError {
+    fn from(e: JsonError) -> Self {
+        NonogramError::Json(e)
+    }
+}
+
+impl From<ShapeError> for NonogramError {
+    fn from(e: ShapeError) -> Self {
+        NonogramError::ShapeMismatch(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Line, NonogramError};
+    use core::error::Error;
+
+    #[test]
+    fn is_an_error_without_std() {
+        let error: &dyn Error = &NonogramError::ClueMismatch(Line::Row(2));
+
+        assert_eq!(
+            error.to_string(),
+            "clue for row 2 does not match the completed grid"
+        );
+        assert!(error.source().is_none());
+    }
+}
@@ -0,0 +1,310 @@
+use super::{Difficulty, Nonogram, SolveOptions};
+use core::cmp::Ordering;
+use core::ops::RangeInclusive;
+use ndarray::Array2;
+use rand::distributions::Bernoulli;
+use rand::rngs::StdRng;
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+use rand::thread_rng;
+use rand::{Rng, SeedableRng};
+
+/// Limits on rating a generated grid unless `GenerateOptions` says otherwise. Together they keep
+/// a single rating to around a second even for sparse grids, where probing is slowest.
+const DEFAULT_RATING_PASSES: usize = 1000;
+const DEFAULT_RATING_LINE_CANDIDATES: usize = 2000;
+
+/// Settings for `Nonogram::generate_with`.
+#[derive(Debug, Clone)]
+pub struct GenerateOptions {
+    pub width: usize,
+    pub height: usize,
+    /// Probability of any single cell being filled, between 0 and 1. Values outside that range
+    /// are clamped to it, and NaN counts as 0.
+    pub density: f64,
+    /// Seed for reproducible puzzles. When `None` one is drawn from the thread RNG, or from
+    /// `Math.random` in the browser.
+    pub seed: Option<u64>,
+    /// Only accept grids that the solver can finish, which also means their solution is unique.
+    pub require_unique: bool,
+    /// The kind of picture to draw.
+    pub pattern: Pattern,
+    /// Number of grids to try before giving up on `require_unique` or `difficulty`.
+    pub max_attempts: usize,
+    /// Only accept puzzles whose `Nonogram::difficulty` falls in this band, such as
+    /// `Difficulty::Hard..=Difficulty::Hard` for puzzles that need probing but no backtracking.
+    ///
+    /// With a band set, `require_unique` only rules out `Difficulty::Unsolvable`.
+    pub difficulty: Option<RangeInclusive<Difficulty>>,
+    /// Budget for rating each grid against `difficulty`, see `Nonogram::difficulty_with_options`.
+    /// A grid whose rating runs out counts as a failed attempt, so one hard grid can't hold up
+    /// generation. The default limits passes and line candidates, which keeps seeded generation
+    /// reproducible; a `max_time` would make it depend on the machine.
+    pub rating: SolveOptions,
+}
+
+/// How the cells of a generated picture relate to each other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pattern {
+    /// Every cell filled independently, which tends to look like noise.
+    Uniform,
+    /// Random cells mirrored so the picture has the given symmetry.
+    Symmetric(Symmetry),
+    /// Random cells smoothed into blobs by repeatedly filling each cell whose 3×3 neighbourhood
+    /// is mostly filled and clearing it when mostly empty.
+    Smoothed { iterations: usize },
+    /// Smooth value noise over a lattice with points `scale` cells apart, with the highest
+    /// values filled. Gives a few large connected shapes. Filled cells match `density` exactly.
+    Noise { scale: usize },
+}
+
+/// The mirror symmetry of a `Pattern::Symmetric` picture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    /// The left and right halves mirror each other.
+    Horizontal,
+    /// The top and bottom halves mirror each other.
+    Vertical,
+    /// The picture looks the same turned upside down.
+    Rotational,
+}
+
+impl Symmetry {
+    /// The cells that must match `(i, j)`, including itself.
+    fn partners(
+        self,
+        (i, j): (usize, usize),
+        (height, width): (usize, usize),
+    ) -> [(usize, usize); 2] {
+        match self {
+            Symmetry::Horizontal => [(i, j), (i, width - 1 - j)],
+            Symmetry::Vertical => [(i, j), (height - 1 - i, j)],
+            Symmetry::Rotational => [(i, j), (height - 1 - i, width - 1 - j)],
+        }
+    }
+}
+
+impl GenerateOptions {
+    /// Options for a half-filled puzzle of the given size with no further requirements.
+    pub fn new(width: usize, height: usize) -> GenerateOptions {
+        GenerateOptions {
+            width,
+            height,
+            density: 0.5,
+            seed: None,
+            require_unique: false,
+            pattern: Pattern::Uniform,
+            max_attempts: 1000,
+            difficulty: None,
+            rating: SolveOptions {
+                max_passes: Some(DEFAULT_RATING_PASSES),
+                max_line_candidates: Some(DEFAULT_RATING_LINE_CANDIDATES),
+                ..SolveOptions::default()
+            },
+        }
+    }
+
+    /// Options for the `index`th puzzle of a batch, each with its own seed derived from this
+    /// one so the whole batch can be reproduced.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn for_batch(&self, base_seed: u64, index: usize) -> GenerateOptions {
+        GenerateOptions {
+            seed: Some(mix_seed(base_seed, index as u64)),
+            ..self.clone()
+        }
+    }
+
+    /// `density` clamped to a probability, since `Bernoulli::new` panics on anything else.
+    pub(crate) fn fill_probability(&self) -> f64 {
+        if self.density.is_nan() {
+            0.0
+        } else {
+            self.density.clamp(0.0, 1.0)
+        }
+    }
+
+    pub(crate) fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::seed_from_u64(fresh_seed()),
+        }
+    }
+}
+
+/// A seed for unseeded generation.
+///
+/// rand cannot reach an entropy source on `wasm32-unknown-unknown` and its thread RNG panics
+/// there, so browser builds take the seed from `Math.random` instead.
+pub(crate) fn fresh_seed() -> u64 {
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    {
+        let half = || (js_sys::Math::random() * 4_294_967_296.0) as u64;
+
+        half() << 32 | half()
+    }
+
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    thread_rng().gen()
+}
+
+/// SplitMix64 finalizer, so neighbouring indexes give unrelated seeds.
+#[cfg(feature = "parallel")]
+fn mix_seed(base: u64, index: u64) -> u64 {
+    let mut z = base.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+pub(crate) fn random_grid<R: Rng>(options: &GenerateOptions, rng: &mut R) -> Array2<u8> {
+    let dim = (options.height, options.width);
+    let filled = Bernoulli::new(options.fill_probability());
+    let mut grid = Array2::from_shape_fn(dim, |_| rng.sample(filled) as u8);
+
+    match options.pattern {
+        Pattern::Uniform => (),
+        Pattern::Symmetric(symmetry) => {
+            for i in 0..options.height {
+                for j in 0..options.width {
+                    let [_, partner] = symmetry.partners((i, j), dim);
+
+                    grid[(i, j)] = grid[partner.min((i, j))];
+                }
+            }
+        }
+        Pattern::Smoothed { iterations } => {
+            for _ in 0..iterations {
+                grid = smooth(&grid);
+            }
+        }
+        Pattern::Noise { scale } => grid = noise_grid(options, scale.max(1), rng),
+    }
+
+    grid
+}
+
+/// Changes a generated picture by one cell, along with whatever cells its pattern ties to it.
+fn flip<R: Rng>(options: &GenerateOptions, grid: &mut Array2<u8>, rng: &mut R) {
+    let cell = (
+        rng.gen_range(0, options.height),
+        rng.gen_range(0, options.width),
+    );
+    let value = grid[cell] ^ 1;
+
+    match options.pattern {
+        Pattern::Symmetric(symmetry) => {
+            for &partner in &symmetry.partners(cell, grid.dim()) {
+                grid[partner] = value;
+            }
+        }
+        _ => grid[cell] = value,
+    }
+}
+
+/// One round of majority voting over each cell's 3×3 neighbourhood, keeping the cell on a tie.
+fn smooth(grid: &Array2<u8>) -> Array2<u8> {
+    let (height, width) = grid.dim();
+
+    Array2::from_shape_fn((height, width), |(i, j)| {
+        let rows = i.saturating_sub(1)..(i + 2).min(height);
+        let columns = j.saturating_sub(1)..(j + 2).min(width);
+        let cells = rows.len() * columns.len();
+        let filled: usize = rows
+            .flat_map(|r| columns.clone().map(move |c| grid[(r, c)] as usize))
+            .sum();
+
+        match (filled * 2).cmp(&cells) {
+            Ordering::Greater => 1,
+            Ordering::Less => 0,
+            Ordering::Equal => grid[(i, j)],
+        }
+    })
+}
+
+/// Fills the `density` share of cells with the highest value noise.
+fn noise_grid<R: Rng>(options: &GenerateOptions, scale: usize, rng: &mut R) -> Array2<u8> {
+    let dim = (options.height, options.width);
+    let lattice = Array2::from_shape_fn(
+        (options.height / scale + 2, options.width / scale + 2),
+        |_| rng.gen::<f64>(),
+    );
+    let fade = |t: f64| t * t * (3.0 - 2.0 * t);
+    let noise = Array2::from_shape_fn(dim, |(i, j)| {
+        let (y, x) = (i as f64 / scale as f64, j as f64 / scale as f64);
+        let (r, c) = (y as usize, x as usize);
+        let (ty, tx) = (fade(y - r as f64), fade(x - c as f64));
+        let top = lattice[(r, c)] * (1.0 - tx) + lattice[(r, c + 1)] * tx;
+        let bottom = lattice[(r + 1, c)] * (1.0 - tx) + lattice[(r + 1, c + 1)] * tx;
+
+        top * (1.0 - ty) + bottom * ty
+    });
+    let mut order: Vec<(usize, usize)> = noise.indexed_iter().map(|(index, _)| index).collect();
+    let filled = (options.fill_probability() * order.len() as f64).round() as usize;
+    let mut grid = Array2::zeros(dim);
+
+    order.sort_by(|&a, &b| noise[b].partial_cmp(&noise[a]).unwrap_or(Ordering::Equal));
+
+    for &cell in order.iter().take(filled) {
+        grid[cell] = 1;
+    }
+
+    grid
+}
+
+/// Walks from a random grid towards the band one flipped cell (and its mirror image) at a time,
+/// keeping each flip that doesn't move the rating further away. Every grid counts as an attempt,
+/// including one whose rating runs out of budget, and the walk starts over from a fresh grid when
+/// it has gone a grid's worth of flips without getting closer.
+pub(crate) fn generate_in_band<R: Rng>(
+    options: &GenerateOptions,
+    band: &RangeInclusive<Difficulty>,
+    rng: &mut R,
+) -> Option<Nonogram> {
+    let accepts = |difficulty: Difficulty| {
+        band.contains(&difficulty)
+            && !(options.require_unique && difficulty == Difficulty::Unsolvable)
+    };
+    let patience = (options.width * options.height).max(1);
+    let mut kept: Option<(Array2<u8>, usize)> = None;
+    let mut stuck = 0;
+
+    for _ in 0..options.max_attempts.max(1) {
+        let grid = match &kept {
+            Some((grid, _)) if !grid.is_empty() => {
+                let mut grid = grid.clone();
+
+                flip(options, &mut grid, rng);
+                grid
+            }
+            _ => random_grid(options, rng),
+        };
+        let nonogram = Nonogram::from_grid(grid);
+
+        match nonogram.difficulty_with_options(&options.rating) {
+            Some(difficulty) if accepts(difficulty) => return Some(nonogram),
+            Some(difficulty) => {
+                let distance = difficulty.distance_to(band).max(1);
+
+                match &kept {
+                    Some((_, kept_distance)) if distance > *kept_distance => stuck += 1,
+                    Some((_, kept_distance)) if distance == *kept_distance => {
+                        stuck += 1;
+                        kept = Some((nonogram.completed_grid, distance));
+                    }
+                    _ => {
+                        stuck = 0;
+                        kept = Some((nonogram.completed_grid, distance));
+                    }
+                }
+            }
+            None => stuck += 1,
+        }
+
+        if stuck >= patience {
+            kept = None;
+            stuck = 0;
+        }
+    }
+
+    None
+}
@@ -0,0 +1,86 @@
+use super::prelude::*;
+use super::search::{enumerate, fix};
+use super::session::solve_from;
+use super::solution::StateRow;
+use super::{CellState, Nonogram, SolveResult, SolveStatus};
+
+/// The state a given reveals for its cell.
+fn state((_, filled): ((usize, usize), bool)) -> CellState {
+    if filled {
+        CellState::Filled
+    } else {
+        CellState::Empty
+    }
+}
+
+/// Candidates with every given already applied.
+fn apply(
+    rows: &[Vec<StateRow>],
+    columns: &[Vec<StateRow>],
+    givens: &[((usize, usize), bool)],
+) -> (Vec<Vec<StateRow>>, Vec<Vec<StateRow>>) {
+    let mut rows = rows.to_vec();
+    let mut columns = columns.to_vec();
+
+    for &given in givens {
+        fix(&mut rows, &mut columns, given.0, state(given));
+    }
+
+    (rows, columns)
+}
+
+impl Nonogram {
+    /// Solves the puzzle with some cells revealed up front, each as `((row, column), filled)`.
+    ///
+    /// Givens that disagree with the clues or with each other end in a contradiction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a given lies outside the puzzle.
+    pub fn solve_with_givens(&self, givens: &[((usize, usize), bool)]) -> SolveResult {
+        let (rows, columns) = enumerate(self);
+        let (rows, columns) = apply(&rows, &columns, givens);
+
+        solve_from(self, &rows, &columns)
+    }
+
+    /// Drops every given the solver can do without, leaving a set it still finishes the puzzle
+    /// from but where each remaining given is needed.
+    ///
+    /// Givens are tried for removal in order, so earlier ones are the first to go. The result is
+    /// minimal in that no single given can be removed, not necessarily the smallest such set.
+    /// Returns `None` when the solver can't finish the puzzle even with every given.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a given lies outside the puzzle.
+    pub fn minimal_givens(
+        &self,
+        givens: &[((usize, usize), bool)],
+    ) -> Option<Vec<((usize, usize), bool)>> {
+        let (rows, columns) = enumerate(self);
+        let solves = |givens: &[((usize, usize), bool)]| {
+            let (rows, columns) = apply(&rows, &columns, givens);
+
+            solve_from(self, &rows, &columns).status == SolveStatus::Solved
+        };
+
+        if !solves(givens) {
+            return None;
+        }
+
+        let mut needed = givens.to_vec();
+        let mut index = 0;
+
+        while index < needed.len() {
+            let given = needed.remove(index);
+
+            if !solves(&needed) {
+                needed.insert(index, given);
+                index += 1;
+            }
+        }
+
+        Some(needed)
+    }
+}
@@ -1,25 +1,81 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+mod ambiguity;
+mod budget;
+mod cnf;
+mod code;
+mod color;
+mod difficulty;
+mod error;
+#[cfg(feature = "std")]
+mod generation;
+mod givens;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod placement;
+mod progress;
+mod search;
+mod session;
 mod solution;
+mod strategy;
+mod suggest;
+mod symmetry;
+#[cfg(feature = "wasm")]
+mod wasm;
 
+extern crate alloc;
 extern crate crc;
 extern crate ndarray;
-extern crate ndarray_rand;
+#[cfg(feature = "std")]
 extern crate rand;
 extern crate serde;
+#[cfg(feature = "std")]
 extern crate serde_json;
 
+use core::hash::{Hash, Hasher};
 use crc::crc64::checksum_ecma;
-use ndarray::{iter::Lanes, ArrayView1, Ix1};
-use ndarray_rand::RandomExt;
-use rand::distributions::Uniform;
+use ndarray::{iter::Lanes, ArrayView1, ErrorKind, Ix1, ShapeError};
+use prelude::*;
+use serde::de::Error as DeError;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "std")]
 use serde_json::Error as JsonError;
-use std::hash::{Hash, Hasher};
 
+pub use ambiguity::AmbiguityReport;
+pub use budget::{CancelToken, SolveOptions};
+pub use cnf::Cnf;
+pub use color::{ColorNonogram, ColorSegment, ColorSolveResult, MAX_COLORS};
+pub use difficulty::Difficulty;
+pub use error::{Line, NonogramError};
+#[cfg(feature = "std")]
+pub use generation::{GenerateOptions, Pattern, Symmetry};
 pub use ndarray::{arr1, arr2, Array1, Array2};
-
-use solution::{
-    common_row_indexes, enumerate_row_states, filter_invalid_row_states, StateGrid, StateRow,
+pub use placement::{count_placements, placements, Placements};
+pub use progress::{Hint, Move, Progress};
+pub use search::Solutions;
+pub use session::SolveSession;
+pub use solution::{CellState, LineCandidates, SolveResult, SolveStats, SolveStatus};
+pub use strategy::{
+    default_strategies, Deduction, EdgeForcing, Enumeration, ExplainedSolve, GapJoining, Glue,
+    LineStrategy, Mercury, SimpleOverlap, SimpleSpaces,
 };
+pub use suggest::{Edit, Suggestion};
+pub use symmetry::{Canonical, Transform};
+#[cfg(feature = "wasm")]
+pub use wasm::Puzzle;
+
+use budget::Budget;
+#[cfg(feature = "std")]
+use generation::{generate_in_band, random_grid};
+use solution::{Execution, StateGrid, StateRow};
+
+/// What the standard prelude would provide, taken from `alloc` so the crate builds without std.
+mod prelude {
+    pub(crate) use alloc::boxed::Box;
+    pub(crate) use alloc::string::{String, ToString};
+    pub(crate) use alloc::vec::Vec;
+    pub(crate) use alloc::{format, vec};
+}
 
 fn build_clue(row: ArrayView1<u8>) -> Vec<usize> {
     let mut clue: Vec<usize> = Vec::new();
@@ -53,24 +109,77 @@ impl Nonogram {
     /// Generates a random nonogram with the given dimensions.
     ///
     /// The generated puzzle is not checked for solvability.
+    #[cfg(feature = "std")]
     pub fn generate(width: usize, height: usize) -> Nonogram {
-        let completed_grid = Array2::random((height, width), Uniform::new_inclusive(0, 1));
+        let options = GenerateOptions::new(width, height);
+
+        Nonogram::from_grid(random_grid(&options, &mut options.rng()))
+    }
+
+    /// Generates a random nonogram according to `options`.
+    ///
+    /// Returns `None` if `require_unique` is set and no uniquely solvable grid turned up within
+    /// `max_attempts` tries.
+    #[cfg(feature = "std")]
+    pub fn generate_with(options: &GenerateOptions) -> Option<Nonogram> {
+        let mut rng = options.rng();
+
+        if let Some(band) = &options.difficulty {
+            return generate_in_band(options, band, &mut rng);
+        }
+
+        for _ in 0..options.max_attempts.max(1) {
+            let nonogram = Nonogram::from_grid(random_grid(options, &mut rng));
 
+            if !options.require_unique || nonogram.solvable() {
+                return Some(nonogram);
+            }
+        }
+
+        None
+    }
+
+    /// Builds a nonogram from a picture of 0s and 1s, deriving its clues.
+    pub fn from_grid(completed_grid: Array2<u8>) -> Nonogram {
         Nonogram {
-            row_segments: build_clues(completed_grid.genrows()),
-            column_segments: build_clues(completed_grid.gencolumns()),
+            row_segments: build_clues(completed_grid.rows()),
+            column_segments: build_clues(completed_grid.columns()),
             completed_grid,
         }
     }
 
-    /// Provides the height of the puzzle.
+    /// Builds a puzzle from its clues alone, for when the intended picture isn't known.
+    ///
+    /// The completed grid of such a puzzle is left empty, with no rows or columns.
+    pub fn from_clues(
+        row_segments: Array1<Vec<usize>>,
+        column_segments: Array1<Vec<usize>>,
+    ) -> Nonogram {
+        Nonogram {
+            row_segments,
+            column_segments,
+            completed_grid: Array2::zeros((0, 0)),
+        }
+    }
+
+    /// Whether the puzzle only has clues, with an empty completed grid.
+    pub fn is_clue_only(&self) -> bool {
+        self.completed_grid.dim() == (0, 0) && (self.height(), self.width()) != (0, 0)
+    }
+
+    /// Provides the height of the puzzle, which is its number of row clues.
+    ///
+    /// Before clue-only puzzles this read the height of the completed grid. The two agree for
+    /// every valid puzzle with a picture, but only the clues give a puzzle from `from_clues` its
+    /// size.
     pub fn height(&self) -> usize {
-        self.completed_grid.dim().0
+        self.row_segments.len()
     }
 
-    /// Provides the width of the puzzle.
+    /// Provides the width of the puzzle, which is its number of column clues. See `height` for
+    /// how this relates to the completed grid.
     pub fn width(&self) -> usize {
-        self.completed_grid.dim().1
+        self.column_segments.len()
     }
 
     /// Determines whether or not the puzzle is solvable.
@@ -78,69 +187,158 @@ impl Nonogram {
     /// This method attempts to programmatically solve the puzzle. If it reaches a dead-end the
     /// method returns false. Otherwise it reaches the conclusion of the puzzle and returns true.
     pub fn solvable(&self) -> bool {
-        let mut row_possibilities: Vec<Vec<StateRow>> = self
-            .row_segments
-            .iter()
-            .map(|clue| enumerate_row_states(self.width(), &clue))
-            .collect();
+        self.solve().status == SolveStatus::Solved
+    }
 
-        let mut column_possibilities: Vec<Vec<StateRow>> = self
-            .column_segments
-            .iter()
-            .map(|clue| enumerate_row_states(self.height(), &clue))
-            .collect();
+    /// Runs the solver and reports how far it got, along with every cell it could determine.
+    pub fn solve(&self) -> SolveResult {
+        self.solve_with_options(&SolveOptions::default())
+    }
+
+    /// Runs the solver within the time, pass and memory limits of `options`.
+    ///
+    /// Stops with `SolveStatus::BudgetExceeded` instead of running on when a limit is reached or
+    /// the cancel token is triggered.
+    pub fn solve_with_options(&self, options: &SolveOptions) -> SolveResult {
+        let budget = Budget::start(options);
+        let execution = Execution::of(options);
+        let mut stats = SolveStats::default();
+
+        let finish = |status, grid: StateGrid, mut stats: SolveStats| {
+            stats.elapsed = budget.elapsed();
+
+            SolveResult {
+                status,
+                grid: grid.into_array(),
+                stats,
+            }
+        };
+
+        let possibilities = execution
+            .enumerate(self.width(), &self.row_segments, &budget)
+            .and_then(|rows| {
+                execution
+                    .enumerate(self.height(), &self.column_segments, &budget)
+                    .map(|columns| (rows, columns))
+            });
+
+        let (mut row_possibilities, mut column_possibilities) = match possibilities {
+            Some(possibilities) => possibilities,
+            None => {
+                let grid = StateGrid::new(self.height(), self.width());
+
+                return finish(SolveStatus::BudgetExceeded, grid, stats);
+            }
+        };
 
+        stats.record_candidates(&row_possibilities, &column_possibilities, true);
+
+        let (status, grid, stats) = self.propagate(
+            &mut row_possibilities,
+            &mut column_possibilities,
+            &budget,
+            execution,
+            stats,
+        );
+
+        finish(status, grid, stats)
+    }
+
+    /// Runs line propagation from already enumerated candidates until it stops making progress.
+    ///
+    /// The candidates are narrowed down in place.
+    pub(crate) fn propagate(
+        &self,
+        row_possibilities: &mut [Vec<StateRow>],
+        column_possibilities: &mut [Vec<StateRow>],
+        budget: &Budget,
+        execution: Execution,
+        mut stats: SolveStats,
+    ) -> (SolveStatus, StateGrid, SolveStats) {
         let mut grid = StateGrid::new(self.height(), self.width());
 
-        loop {
+        if row_possibilities.iter().any(|p| p.is_empty())
+            || column_possibilities.iter().any(|p| p.is_empty())
+        {
+            return (SolveStatus::Contradiction, grid, stats);
+        }
+
+        let status = loop {
+            if !budget.allows_pass(stats.passes) {
+                break SolveStatus::BudgetExceeded;
+            }
+
             let mut changes = 0;
+            let mut cells = 0;
+            let mut contradiction = false;
 
-            for (index, possibilities) in row_possibilities.iter_mut().enumerate() {
-                let common = common_row_indexes(&possibilities);
+            stats.passes += 1;
+            stats.lines_processed += self.height() + self.width();
 
-                for cell in &common {
-                    if let Some(current_cell) = grid.get(index, cell.0) {
-                        if *current_cell != cell.1 {
-                            grid.set(index, cell.0, cell.1);
-                            changes += 1;
-                        }
-                    }
-                }
+            let rows: Vec<StateRow> = (0..self.height()).map(|i| grid.get_row(i)).collect();
 
-                let filtered_possibilities =
-                    filter_invalid_row_states(&grid.get_row(index), &possibilities);
+            for (i, update) in execution
+                .propagate(&rows, row_possibilities)
+                .into_iter()
+                .enumerate()
+            {
+                for &(j, state) in &update.cells {
+                    grid.set(i, j, state);
+                }
 
-                changes += possibilities.len() - filtered_possibilities.len();
-                *possibilities = filtered_possibilities;
+                cells += update.cells.len();
+                changes += update.cells.len() + update.removed;
+                contradiction |= update.contradiction;
             }
 
-            for (index, possibilities) in column_possibilities.iter_mut().enumerate() {
-                let common = common_row_indexes(&possibilities);
+            let columns: Vec<StateRow> = (0..self.width()).map(|j| grid.get_column(j)).collect();
 
-                for cell in &common {
-                    if let Some(current_cell) = grid.get(cell.0, index) {
-                        if *current_cell != cell.1 {
-                            grid.set(cell.0, index, cell.1);
-                            changes += 1;
-                        }
-                    }
+            for (j, update) in execution
+                .propagate(&columns, column_possibilities)
+                .into_iter()
+                .enumerate()
+            {
+                for &(i, state) in &update.cells {
+                    grid.set(i, j, state);
                 }
 
-                let filtered_possibilities =
-                    filter_invalid_row_states(&grid.get_column(index), &possibilities);
+                cells += update.cells.len();
+                changes += update.cells.len() + update.removed;
+                contradiction |= update.contradiction;
+            }
+
+            stats.cells_per_pass.push(cells);
 
-                changes += possibilities.len() - filtered_possibilities.len();
-                *possibilities = filtered_possibilities;
+            if contradiction
+                || row_possibilities.iter().any(|p| p.is_empty())
+                || column_possibilities.iter().any(|p| p.is_empty())
+            {
+                break SolveStatus::Contradiction;
             }
 
             if changes == 0 {
-                break false;
+                break SolveStatus::Stalled;
             }
 
+            // The columns were narrowed last, so only the rows can disagree with the cells the
+            // columns just settled.
             if grid.is_known() {
-                break true;
+                let rows_fit = row_possibilities
+                    .iter()
+                    .enumerate()
+                    .all(|(i, possibilities)| possibilities.contains(&grid.get_row(i)));
+
+                if rows_fit {
+                    break SolveStatus::Solved;
+                }
+
+                break SolveStatus::Contradiction;
             }
-        }
+        };
+
+        stats.record_candidates(row_possibilities, column_possibilities, false);
+
+        (status, grid, stats)
     }
 
     /// Generates a checksum for quickly determining equivalence between puzzles of
@@ -148,7 +346,8 @@ impl Nonogram {
     ///
     /// This is *not* meant to check equivalence between all puzzles, only those with the
     /// same dimensions. This function is meant for cross-platform checks for equivalence in those
-    /// situations.
+    /// situations. `canonical_checksum` covers puzzles of any size and ignores rotations and
+    /// reflections.
     pub fn generate_checksum(&self) -> u64 {
         let mut aggregate: Vec<u8> = Vec::new();
 
@@ -159,35 +358,150 @@ impl Nonogram {
         checksum_ecma(aggregate.as_slice())
     }
 
+    /// A stable checksum of the puzzle's clues, suitable for storing in a database index.
+    ///
+    /// This is the CRC-64/ECMA of the number of rows and columns followed by every row clue and
+    /// then every column clue, each clue written as its number of segments and then the segment
+    /// lengths. All numbers are little-endian `u64`s, so the value does not depend on platform or
+    /// crate version.
+    pub fn clue_fingerprint(&self) -> u64 {
+        let mut aggregate: Vec<u8> = Vec::new();
+        let mut push = |number: usize| aggregate.extend_from_slice(&(number as u64).to_le_bytes());
+
+        push(self.row_segments.len());
+        push(self.column_segments.len());
+
+        for clue in self.row_segments.iter().chain(self.column_segments.iter()) {
+            push(clue.len());
+            clue.iter().for_each(|&segment| push(segment));
+        }
+
+        checksum_ecma(aggregate.as_slice())
+    }
+
     /// Serializes the nonogram as json so that we don't need to use serde every time we need to use it
+    #[cfg(feature = "std")]
     pub fn as_json(&self) -> Result<String, JsonError> {
         serde_json::to_string(&SerializedNonogram::from_nonogram(self))
     }
 
-    pub fn from_json(serialized: &str) -> Result<Nonogram, String> {
-        match serde_json::from_str::<SerializedNonogram>(serialized) {
-            Ok(deserialized) => match deserialized.to_nonogram() {
-                Ok(nonogram) => Ok(nonogram),
-                Err(e) => Err(e.to_string()),
-            },
-            Err(e) => Err(e.to_string()),
+    /// Deserializes a nonogram from json, validating it against its stored checksum.
+    #[cfg(feature = "std")]
+    pub fn from_json(serialized: &str) -> Result<Nonogram, NonogramError> {
+        serde_json::from_str::<SerializedNonogram>(serialized)?.to_nonogram()
+    }
+
+    /// Serializes the nonogram as CBOR.
+    #[cfg(feature = "cbor")]
+    pub fn as_cbor(&self) -> Result<Vec<u8>, NonogramError> {
+        let mut serialized = Vec::new();
+
+        ciborium::ser::into_writer(&SerializedNonogram::from_nonogram(self), &mut serialized)
+            .map_err(NonogramError::CborEncode)?;
+
+        Ok(serialized)
+    }
+
+    /// Deserializes a nonogram from CBOR, validating it against its stored checksum.
+    #[cfg(feature = "cbor")]
+    pub fn from_cbor(serialized: &[u8]) -> Result<Nonogram, NonogramError> {
+        ciborium::de::from_reader::<SerializedNonogram, _>(serialized)
+            .map_err(NonogramError::CborDecode)?
+            .to_nonogram()
+    }
+
+    /// Serializes the nonogram as MessagePack, with structs written as arrays rather than maps.
+    #[cfg(feature = "msgpack")]
+    pub fn as_msgpack(&self) -> Result<Vec<u8>, NonogramError> {
+        rmp_serde::to_vec(&SerializedNonogram::from_nonogram(self))
+            .map_err(NonogramError::MessagePackEncode)
+    }
+
+    /// Deserializes a nonogram from MessagePack, validating it against its stored checksum.
+    #[cfg(feature = "msgpack")]
+    pub fn from_msgpack(serialized: &[u8]) -> Result<Nonogram, NonogramError> {
+        rmp_serde::from_slice::<SerializedNonogram>(serialized)
+            .map_err(NonogramError::MessagePackDecode)?
+            .to_nonogram()
+    }
+
+    /// Serializes the nonogram with bincode.
+    #[cfg(feature = "bincode")]
+    pub fn as_bincode(&self) -> Result<Vec<u8>, NonogramError> {
+        bincode::serialize(&SerializedNonogram::from_nonogram(self)).map_err(NonogramError::Bincode)
+    }
+
+    /// Deserializes a nonogram from bincode, validating it against its stored checksum.
+    #[cfg(feature = "bincode")]
+    pub fn from_bincode(serialized: &[u8]) -> Result<Nonogram, NonogramError> {
+        bincode::deserialize::<SerializedNonogram>(serialized)
+            .map_err(NonogramError::Bincode)?
+            .to_nonogram()
+    }
+
+    /// Checks that the completed grid only holds 0s and 1s and that every clue describes its
+    /// line of the completed grid.
+    ///
+    /// Clue-only puzzles just have their clues checked to fit in their lines.
+    pub fn validate(&self) -> Result<(), NonogramError> {
+        let fits = |clue: &Vec<usize>, size: usize| {
+            clue.iter().sum::<usize>() + clue.len().saturating_sub(1) <= size
+        };
+
+        if let Some(index) =
+            (0..self.height()).find(|&i| !fits(&self.row_segments[i], self.width()))
+        {
+            return Err(NonogramError::ClueMismatch(Line::Row(index)));
+        }
+
+        if let Some(index) =
+            (0..self.width()).find(|&j| !fits(&self.column_segments[j], self.height()))
+        {
+            return Err(NonogramError::ClueMismatch(Line::Column(index)));
+        }
+
+        if self.is_clue_only() {
+            return Ok(());
+        }
+
+        if let Some(((row, column), &value)) = self
+            .completed_grid
+            .indexed_iter()
+            .find(|(_, &cell)| cell > 1)
+        {
+            return Err(NonogramError::InvalidCell { row, column, value });
+        }
+
+        if self.completed_grid.dim() != (self.height(), self.width()) {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape).into());
         }
+
+        let rows = build_clues(self.completed_grid.rows());
+        let columns = build_clues(self.completed_grid.columns());
+
+        if let Some(index) = (0..rows.len()).find(|&i| rows[i] != self.row_segments[i]) {
+            return Err(NonogramError::ClueMismatch(Line::Row(index)));
+        }
+
+        if let Some(index) = (0..columns.len()).find(|&i| columns[i] != self.column_segments[i]) {
+            return Err(NonogramError::ClueMismatch(Line::Column(index)));
+        }
+
+        Ok(())
     }
 }
 
+/// Puzzles are identified by their clues, which is all a solver gets to see. Two puzzles with
+/// the same clues are equal even if their completed grids are different solutions to them.
 impl Hash for Nonogram {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.height().hash(state);
-        self.width().hash(state);
-        self.generate_checksum().hash(state);
+        self.clue_fingerprint().hash(state);
     }
 }
 
 impl PartialEq for Nonogram {
     fn eq(&self, other: &Self) -> bool {
-        self.height() == other.height()
-            && self.width() == other.width()
-            && self.generate_checksum() == other.generate_checksum()
+        self.row_segments == other.row_segments && self.column_segments == other.column_segments
     }
 }
 
@@ -207,16 +521,16 @@ impl<'de> Deserialize<'de> for Nonogram {
     where
         D: Deserializer<'de>,
     {
-        match SerializedNonogram::deserialize(deserializer) {
-            Ok(deserialized) => Ok(deserialized.to_nonogram().unwrap()),
-            Err(e) => Err(e),
-        }
+        SerializedNonogram::deserialize(deserializer)?
+            .to_nonogram()
+            .map_err(D::Error::custom)
     }
 }
 
 #[derive(Serialize, Deserialize)]
 struct SerializedNonogram {
-    checksum: String,
+    #[serde(with = "checksum_format")]
+    checksum: u64,
     height: usize,
     width: usize,
     row_segments: Vec<Vec<usize>>,
@@ -227,33 +541,97 @@ struct SerializedNonogram {
 impl SerializedNonogram {
     fn from_nonogram(original: &Nonogram) -> SerializedNonogram {
         SerializedNonogram {
-            checksum: original.generate_checksum().to_string(),
+            checksum: original.generate_checksum(),
             height: original.height(),
             width: original.width(),
             row_segments: original.row_segments.iter().cloned().collect(),
             column_segments: original.column_segments.iter().cloned().collect(),
             completed_grid: original
                 .completed_grid
-                .genrows()
+                .rows()
                 .into_iter()
                 .map(|row| row.iter().cloned().collect())
                 .collect(),
         }
     }
 
-    fn to_nonogram(&self) -> Result<Nonogram, String> {
-        let grid = Array2::from_shape_vec(
+    fn to_nonogram(&self) -> Result<Nonogram, NonogramError> {
+        if self.completed_grid.is_empty()
+            && self.row_segments.len() == self.height
+            && self.column_segments.len() == self.width
+        {
+            return self.to_clue_only_nonogram();
+        }
+
+        let completed_grid = Array2::from_shape_vec(
             (self.height, self.width),
             self.completed_grid.iter().flatten().cloned().collect(),
+        )?;
+
+        if self
+            .completed_grid
+            .iter()
+            .any(|row| row.len() != self.width)
+        {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape).into());
+        }
+
+        let nonogram = Nonogram {
+            row_segments: arr1(self.row_segments.as_slice()),
+            column_segments: arr1(self.column_segments.as_slice()),
+            completed_grid,
+        };
+
+        nonogram.validate()?;
+        self.check_checksum(nonogram)
+    }
+
+    fn to_clue_only_nonogram(&self) -> Result<Nonogram, NonogramError> {
+        let nonogram = Nonogram::from_clues(
+            arr1(self.row_segments.as_slice()),
+            arr1(self.column_segments.as_slice()),
         );
 
-        match grid {
-            Ok(grid_array) => Ok(Nonogram {
-                row_segments: arr1(self.row_segments.as_slice()),
-                column_segments: arr1(self.column_segments.as_slice()),
-                completed_grid: grid_array,
-            }),
-            Err(e) => Err(e.to_string()),
+        nonogram.validate()?;
+        self.check_checksum(nonogram)
+    }
+
+    fn check_checksum(&self, nonogram: Nonogram) -> Result<Nonogram, NonogramError> {
+        let computed = nonogram.generate_checksum();
+
+        if computed != self.checksum {
+            return Err(NonogramError::ChecksumMismatch {
+                stored: self.checksum,
+                computed,
+            });
+        }
+
+        Ok(nonogram)
+    }
+}
+
+/// Human-readable formats get the checksum as a string so that it survives JavaScript's 53-bit
+/// integers. Binary formats store the plain `u64`.
+mod checksum_format {
+    use super::prelude::*;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(checksum: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&checksum.to_string())
+        } else {
+            serializer.serialize_u64(*checksum)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        if deserializer.is_human_readable() {
+            String::deserialize(deserializer)?
+                .parse()
+                .map_err(D::Error::custom)
+        } else {
+            u64::deserialize(deserializer)
         }
     }
 }
@@ -0,0 +1,63 @@
+use super::budget::Budget;
+use super::generation::fresh_seed;
+use super::solution::{enumerate_line, propagate_line, LineUpdate, StateRow};
+use super::{GenerateOptions, Nonogram, SolveOptions, SolveResult};
+use ndarray::Array1;
+use rayon::prelude::*;
+
+pub(crate) fn enumerate(
+    size: usize,
+    clues: &Array1<Vec<usize>>,
+    budget: &Budget,
+) -> Option<Vec<Vec<StateRow>>> {
+    let clues: Vec<&Vec<usize>> = clues.iter().collect();
+
+    clues
+        .par_iter()
+        .map(|clue| enumerate_line(size, clue, budget))
+        .collect()
+}
+
+pub(crate) fn propagate(
+    lines: &[StateRow],
+    possibilities: &mut [Vec<StateRow>],
+) -> Vec<LineUpdate> {
+    lines
+        .par_iter()
+        .zip(possibilities.par_iter_mut())
+        .map(|(line, possibilities)| propagate_line(line, possibilities))
+        .collect()
+}
+
+impl Nonogram {
+    /// Same as `solve`, but enumerates and propagates the lines of each pass on the rayon thread
+    /// pool.
+    ///
+    /// Rows only ever touch their own cells, as do columns, so the result matches `solve`. The
+    /// overhead only pays off for large puzzles, roughly 50x50 and up.
+    pub fn solve_parallel(&self) -> SolveResult {
+        self.solve_with_options(&SolveOptions {
+            parallel: true,
+            ..SolveOptions::default()
+        })
+    }
+
+    /// Solves every puzzle on the rayon thread pool, returning the results in the same order.
+    pub fn solve_all(nonograms: &[Nonogram]) -> Vec<SolveResult> {
+        nonograms.par_iter().map(Nonogram::solve).collect()
+    }
+
+    /// Runs `generate_with` `n` times on the rayon thread pool.
+    ///
+    /// Each puzzle gets its own seed derived from `options.seed` and its index, so a seeded batch
+    /// comes out the same no matter how many threads run it. Puzzles that ran out of attempts are
+    /// `None`.
+    pub fn generate_many(n: usize, options: &GenerateOptions) -> Vec<Option<Nonogram>> {
+        let base_seed = options.seed.unwrap_or_else(fresh_seed);
+
+        (0..n)
+            .into_par_iter()
+            .map(|index| Nonogram::generate_with(&options.for_batch(base_seed, index)))
+            .collect()
+    }
+}
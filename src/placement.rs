@@ -0,0 +1,276 @@
+use super::prelude::*;
+use super::CellState;
+
+/// Whether blocks `k..` of the clue can be placed in `line[p..]`, for every `k` and `p`.
+pub(crate) fn fits_table(clue: &[usize], line: &[CellState]) -> Vec<Vec<bool>> {
+    let n = line.len();
+    let mut fits = vec![vec![false; n + 1]; clue.len() + 1];
+
+    fits[clue.len()][n] = true;
+
+    for p in (0..n).rev() {
+        fits[clue.len()][p] = line[p] != CellState::Filled && fits[clue.len()][p + 1];
+    }
+
+    for k in (0..clue.len()).rev() {
+        for p in (0..n).rev() {
+            let skip = line[p] != CellState::Filled && fits[k][p + 1];
+
+            fits[k][p] = skip || block_fits(clue, line, &fits, k, p);
+        }
+    }
+
+    fits
+}
+
+/// Whether block `k` can start at `p` with the rest of the clue still fitting after it.
+pub(crate) fn block_fits(
+    clue: &[usize],
+    line: &[CellState],
+    fits: &[Vec<bool>],
+    k: usize,
+    p: usize,
+) -> bool {
+    let end = p + clue[k];
+
+    if end > line.len() || line[p..end].contains(&CellState::Empty) {
+        return false;
+    }
+
+    if end == line.len() {
+        fits[k + 1][end]
+    } else {
+        line[end] != CellState::Filled && fits[k + 1][end + 1]
+    }
+}
+
+/// Lazily walks every placement of a clue that agrees with the known cells of a line.
+///
+/// Placements come out in order of their block positions, leftmost first, each as a fully known
+/// line. Placements that can't be finished are pruned up front, so every step does at most a
+/// line's worth of work and nothing is produced twice.
+#[derive(Clone, Debug)]
+pub struct Placements {
+    clue: Vec<usize>,
+    line: Vec<CellState>,
+    fits: Vec<Vec<bool>>,
+    starts: Vec<usize>,
+    first: bool,
+    done: bool,
+}
+
+/// Placements of `clue` in `line` that keep every filled and empty cell of the line.
+///
+/// Pass a line of unknown cells for every placement of the clue in a line of that length.
+pub fn placements(clue: &[usize], line: &[CellState]) -> Placements {
+    let fits = fits_table(clue, line);
+    let done = !fits[0][0];
+
+    Placements {
+        clue: clue.to_vec(),
+        line: line.to_vec(),
+        fits,
+        starts: Vec::with_capacity(clue.len()),
+        first: true,
+        done,
+    }
+}
+
+impl Placements {
+    /// Where the gap before block `k` begins.
+    fn gap_start(&self, k: usize) -> usize {
+        if k == 0 {
+            0
+        } else {
+            self.starts[k - 1] + self.clue[k - 1] + 1
+        }
+    }
+
+    /// The first start at or after `from` for block `k`, without skipping over a filled cell
+    /// that the block would then leave uncovered.
+    fn next_start(&self, k: usize, from: usize) -> Option<usize> {
+        let gap_start = self.gap_start(k);
+
+        (from..self.line.len())
+            .take_while(|&s| s == gap_start || self.line[s - 1] != CellState::Filled)
+            .find(|&s| block_fits(&self.clue, &self.line, &self.fits, k, s))
+    }
+
+    /// Places blocks `k..` as far left as they go. Always succeeds when block `k - 1` fits.
+    fn settle_from(&mut self, k: usize) {
+        self.starts.truncate(k);
+
+        for block in k..self.clue.len() {
+            let start = self.next_start(block, self.gap_start(block)).unwrap();
+
+            self.starts.push(start);
+        }
+    }
+
+    fn render(&self) -> Vec<CellState> {
+        let mut cells = vec![CellState::Empty; self.line.len()];
+
+        for (&start, &length) in self.starts.iter().zip(&self.clue) {
+            for cell in &mut cells[start..start + length] {
+                *cell = CellState::Filled;
+            }
+        }
+
+        cells
+    }
+}
+
+impl Iterator for Placements {
+    type Item = Vec<CellState>;
+
+    fn next(&mut self) -> Option<Vec<CellState>> {
+        if self.done {
+            return None;
+        }
+
+        if self.first {
+            self.first = false;
+            self.settle_from(0);
+
+            return Some(self.render());
+        }
+
+        for k in (0..self.clue.len()).rev() {
+            if let Some(start) = self.next_start(k, self.starts[k] + 1) {
+                self.starts.truncate(k);
+                self.starts.push(start);
+                self.settle_from(k + 1);
+
+                return Some(self.render());
+            }
+        }
+
+        self.done = true;
+        None
+    }
+}
+
+/// The exact number of placements `placements` would produce, without producing them.
+///
+/// Counts past `u128::MAX` saturate, which takes lines far longer than any real puzzle.
+pub fn count_placements(clue: &[usize], line: &[CellState]) -> u128 {
+    let n = line.len();
+    let mut counts = vec![vec![0u128; n + 1]; clue.len() + 1];
+
+    counts[clue.len()][n] = 1;
+
+    for p in (0..n).rev() {
+        if line[p] != CellState::Filled {
+            counts[clue.len()][p] = counts[clue.len()][p + 1];
+        }
+    }
+
+    for k in (0..clue.len()).rev() {
+        for p in (0..n).rev() {
+            let skip = if line[p] != CellState::Filled {
+                counts[k][p + 1]
+            } else {
+                0
+            };
+            let end = p + clue[k];
+            let place = if end > n || line[p..end].contains(&CellState::Empty) {
+                0
+            } else if end == n {
+                counts[k + 1][end]
+            } else if line[end] != CellState::Filled {
+                counts[k + 1][end + 1]
+            } else {
+                0
+            };
+
+            counts[k][p] = skip.saturating_add(place);
+        }
+    }
+
+    counts[0][0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_placements, placements};
+    use crate::CellState::{self, Empty, Filled, Unknown};
+
+    fn line(cells: &str) -> Vec<CellState> {
+        cells
+            .chars()
+            .map(|c| match c {
+                '#' => Filled,
+                'x' => Empty,
+                _ => Unknown,
+            })
+            .collect()
+    }
+
+    fn show(placement: &[CellState]) -> String {
+        placement
+            .iter()
+            .map(|&cell| if cell == Filled { '#' } else { 'x' })
+            .collect()
+    }
+
+    #[test]
+    fn placements_in_order() {
+        let found: Vec<String> = placements(&[2, 1], &line("......"))
+            .map(|p| show(&p))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec!["##x#xx", "##xx#x", "##xxx#", "x##x#x", "x##xx#", "xx##x#"]
+        );
+    }
+
+    #[test]
+    fn placements_respect_known_cells() {
+        let found: Vec<String> = placements(&[1, 1], &line("..#.x"))
+            .map(|p| show(&p))
+            .collect();
+
+        assert_eq!(found, vec!["#x#xx"]);
+    }
+
+    #[test]
+    fn placements_cover_filled_cells() {
+        let found: Vec<String> = placements(&[1], &line("#..#")).map(|p| show(&p)).collect();
+
+        assert!(found.is_empty());
+        assert_eq!(count_placements(&[1], &line("#..#")), 0);
+    }
+
+    #[test]
+    fn empty_clue_has_one_placement() {
+        assert_eq!(placements(&[], &line("...")).count(), 1);
+        assert_eq!(placements(&[], &line(".#.")).count(), 0);
+        assert_eq!(count_placements(&[], &line("...")), 1);
+    }
+
+    #[test]
+    fn count_matches_binomial() {
+        // C(free cells + blocks, blocks) with 30 - (11 + 3) = 16 free cells.
+        assert_eq!(
+            count_placements(&[3, 3, 3, 2], &line(&".".repeat(30))),
+            4845
+        );
+        assert_eq!(
+            placements(&[3, 3, 3, 2], &line(&".".repeat(30))).count(),
+            4845
+        );
+    }
+
+    #[test]
+    fn count_matches_iterator_on_partial_lines() {
+        for cells in &["..#...x...", "#.........", "...x..#..#", "xxxxxxxxxx"] {
+            for clue in &[vec![2, 1], vec![1, 1, 1], vec![3], vec![]] {
+                assert_eq!(
+                    count_placements(clue, &line(cells)),
+                    placements(clue, &line(cells)).count() as u128
+                );
+            }
+        }
+    }
+}
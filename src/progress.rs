@@ -0,0 +1,553 @@
+use super::prelude::*;
+use super::{default_strategies, CellState, Line, Nonogram, NonogramError};
+use core::convert::TryFrom;
+use ndarray::{Array2, ArrayView1, ErrorKind, ShapeError};
+use serde::{Deserialize, Serialize};
+
+/// A single change to the player's grid.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Move {
+    pub row: usize,
+    pub column: usize,
+    pub before: CellState,
+    pub after: CellState,
+}
+
+/// A suggestion for the player's next move, from `Progress::hint`.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Hint {
+    /// The cell contradicts the completed grid and should be cleared.
+    Mistake { row: usize, column: usize },
+    /// The cell follows from one line's clue and the cells already marked in that line.
+    Deduction {
+        row: usize,
+        column: usize,
+        state: CellState,
+        line: Line,
+        /// Name of the simplest `LineStrategy` that finds it.
+        rule: &'static str,
+    },
+    /// No single line settles anything yet, so the cell is revealed from the solution.
+    Reveal {
+        row: usize,
+        column: usize,
+        state: CellState,
+    },
+}
+
+/// A player's attempt at a puzzle.
+///
+/// The player's grid uses `Filled` for filled cells, `Empty` for cells the player crossed out and
+/// `Unknown` for cells they haven't touched. Every change goes through the move history so it
+/// can be undone and redone, and the whole session can be saved with serde.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "SerializedProgress")]
+pub struct Progress {
+    puzzle: Nonogram,
+    cells: Array2<CellState>,
+    undo: Vec<Move>,
+    redo: Vec<Move>,
+}
+
+#[derive(Deserialize)]
+struct SerializedProgress {
+    puzzle: Nonogram,
+    cells: Array2<CellState>,
+    undo: Vec<Move>,
+    redo: Vec<Move>,
+}
+
+impl TryFrom<SerializedProgress> for Progress {
+    type Error = NonogramError;
+
+    fn try_from(serialized: SerializedProgress) -> Result<Progress, NonogramError> {
+        let dim = serialized.cells.dim();
+        let in_bounds = |m: &Move| m.row < dim.0 && m.column < dim.1;
+
+        if dim != (serialized.puzzle.height(), serialized.puzzle.width())
+            || !serialized.undo.iter().all(in_bounds)
+            || !serialized.redo.iter().all(in_bounds)
+        {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape).into());
+        }
+
+        Ok(Progress {
+            puzzle: serialized.puzzle,
+            cells: serialized.cells,
+            undo: serialized.undo,
+            redo: serialized.redo,
+        })
+    }
+}
+
+fn line_clue(line: ArrayView1<CellState>) -> Vec<usize> {
+    let mut clue = Vec::new();
+    let mut run = 0;
+
+    for &cell in line.iter() {
+        if cell == CellState::Filled {
+            run += 1;
+        } else if run > 0 {
+            clue.push(run);
+            run = 0;
+        }
+    }
+
+    if run > 0 {
+        clue.push(run);
+    }
+
+    clue
+}
+
+/// Matches the closed runs at either end of the line to the clue, stopping at the first unknown
+/// cell or mismatched run from each side.
+fn completed_segments(clue: &[usize], line: ArrayView1<CellState>) -> Vec<bool> {
+    if line_clue(line) == clue {
+        return vec![true; clue.len()];
+    }
+
+    let cells: Vec<CellState> = line.iter().cloned().collect();
+    let mut completed = vec![false; clue.len()];
+    let mut matched_left = 0;
+    let mut i = 0;
+
+    while i < cells.len() && matched_left < clue.len() {
+        match cells[i] {
+            CellState::Empty => i += 1,
+            CellState::Unknown => break,
+            CellState::Filled => {
+                let end = i + cells[i..]
+                    .iter()
+                    .take_while(|&&cell| cell == CellState::Filled)
+                    .count();
+                let closed = end == cells.len() || cells[end] == CellState::Empty;
+
+                if !closed || end - i != clue[matched_left] {
+                    break;
+                }
+
+                completed[matched_left] = true;
+                matched_left += 1;
+                i = end;
+            }
+        }
+    }
+
+    let mut matched_right = clue.len();
+    let mut j = cells.len();
+
+    while j > 0 && matched_right > matched_left {
+        match cells[j - 1] {
+            CellState::Empty => j -= 1,
+            CellState::Unknown => break,
+            CellState::Filled => {
+                let start = j - cells[..j]
+                    .iter()
+                    .rev()
+                    .take_while(|&&cell| cell == CellState::Filled)
+                    .count();
+                let closed = start == 0 || cells[start - 1] == CellState::Empty;
+
+                if !closed || j - start != clue[matched_right - 1] {
+                    break;
+                }
+
+                completed[matched_right - 1] = true;
+                matched_right -= 1;
+                j = start;
+            }
+        }
+    }
+
+    completed
+}
+
+impl Progress {
+    /// Starts a fresh attempt at `puzzle` with every cell unknown.
+    pub fn new(puzzle: Nonogram) -> Progress {
+        Progress {
+            cells: Array2::default((puzzle.height(), puzzle.width())),
+            puzzle,
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    pub fn puzzle(&self) -> &Nonogram {
+        &self.puzzle
+    }
+
+    /// The player's grid.
+    pub fn cells(&self) -> &Array2<CellState> {
+        &self.cells
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> Option<CellState> {
+        self.cells.get((row, column)).cloned()
+    }
+
+    /// Sets a cell and records the move, clearing anything that could have been redone.
+    ///
+    /// Returns the move, or `None` if the cell is out of bounds or already holds `state`.
+    pub fn set(&mut self, row: usize, column: usize, state: CellState) -> Option<Move> {
+        let before = self.get(row, column)?;
+
+        if before == state {
+            return None;
+        }
+
+        let change = Move {
+            row,
+            column,
+            before,
+            after: state,
+        };
+
+        self.cells[[row, column]] = state;
+        self.undo.push(change);
+        self.redo.clear();
+
+        Some(change)
+    }
+
+    /// Sets a cell to `state`, or back to `Unknown` if it already holds `state`.
+    pub fn toggle(&mut self, row: usize, column: usize, state: CellState) -> Option<Move> {
+        if self.get(row, column)? == state {
+            self.set(row, column, CellState::Unknown)
+        } else {
+            self.set(row, column, state)
+        }
+    }
+
+    /// Reverts the most recent move and returns it.
+    pub fn undo(&mut self) -> Option<Move> {
+        let change = self.undo.pop()?;
+
+        self.cells[[change.row, change.column]] = change.before;
+        self.redo.push(change);
+
+        Some(change)
+    }
+
+    /// Reapplies the most recently undone move and returns it.
+    pub fn redo(&mut self) -> Option<Move> {
+        let change = self.redo.pop()?;
+
+        self.cells[[change.row, change.column]] = change.after;
+        self.undo.push(change);
+
+        Some(change)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Whether the filled cells of row `i` spell out its clue.
+    pub fn row_satisfied(&self, i: usize) -> bool {
+        line_clue(self.cells.row(i)) == self.puzzle.row_segments[i]
+    }
+
+    /// Whether the filled cells of column `j` spell out its clue.
+    pub fn column_satisfied(&self, j: usize) -> bool {
+        line_clue(self.cells.column(j)) == self.puzzle.column_segments[j]
+    }
+
+    /// Which segments of row `i`'s clue the player has unambiguously finished.
+    ///
+    /// A segment counts as finished when it is matched by a run of filled cells closed off by
+    /// crosses or the edge, with nothing unknown between it and the end of the line.
+    pub fn completed_row_segments(&self, i: usize) -> Vec<bool> {
+        completed_segments(&self.puzzle.row_segments[i], self.cells.row(i))
+    }
+
+    /// Which segments of column `j`'s clue the player has unambiguously finished.
+    pub fn completed_column_segments(&self, j: usize) -> Vec<bool> {
+        completed_segments(&self.puzzle.column_segments[j], self.cells.column(j))
+    }
+
+    /// Cells the player filled or crossed against the puzzle's completed grid.
+    ///
+    /// Clue-only puzzles have nothing to compare against, so they never report mistakes.
+    pub fn mistakes(&self) -> Vec<(usize, usize)> {
+        if self.puzzle.is_clue_only() {
+            return Vec::new();
+        }
+
+        self.cells
+            .indexed_iter()
+            .filter(|&(index, &cell)| {
+                let filled = self.puzzle.completed_grid[index] == 1;
+
+                (cell == CellState::Filled && !filled) || (cell == CellState::Empty && filled)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Suggests what to do next: fix the first mistake, otherwise mark a cell that one row or
+    /// column already settles, trying the simplest rules first, and failing that reveal a cell.
+    ///
+    /// Returns `None` once every cell is marked, or for clue-only puzzles with no solution.
+    pub fn hint(&self) -> Option<Hint> {
+        if let Some(&(row, column)) = self.mistakes().first() {
+            return Some(Hint::Mistake { row, column });
+        }
+
+        let lines = (0..self.puzzle.height())
+            .map(Line::Row)
+            .chain((0..self.puzzle.width()).map(Line::Column));
+
+        for strategy in default_strategies() {
+            for line in lines.clone() {
+                let (clue, cells) = match line {
+                    Line::Row(i) => (&self.puzzle.row_segments[i], self.cells.row(i)),
+                    Line::Column(j) => (&self.puzzle.column_segments[j], self.cells.column(j)),
+                };
+                let cells: Vec<CellState> = cells.to_vec();
+                let deduced = strategy.deduce(clue, &cells).unwrap_or_default();
+
+                if let Some(&(index, state)) = deduced.first() {
+                    let (row, column) = match line {
+                        Line::Row(i) => (i, index),
+                        Line::Column(j) => (index, j),
+                    };
+
+                    return Some(Hint::Deduction {
+                        row,
+                        column,
+                        state,
+                        line,
+                        rule: strategy.name(),
+                    });
+                }
+            }
+        }
+
+        let ((row, column), _) = self
+            .cells
+            .indexed_iter()
+            .find(|&(_, &cell)| cell == CellState::Unknown)?;
+        let filled = if self.puzzle.is_clue_only() {
+            self.puzzle.solutions(1).grids.first()?[(row, column)] == 1
+        } else {
+            self.puzzle.completed_grid[(row, column)] == 1
+        };
+        let state = if filled {
+            CellState::Filled
+        } else {
+            CellState::Empty
+        };
+
+        Some(Hint::Reveal { row, column, state })
+    }
+
+    /// Whether the filled cells match the completed grid exactly. Unknown cells count as empty.
+    ///
+    /// Clue-only puzzles are complete once every row and column is satisfied.
+    pub fn is_complete(&self) -> bool {
+        if self.puzzle.is_clue_only() {
+            return (0..self.puzzle.height()).all(|i| self.row_satisfied(i))
+                && (0..self.puzzle.width()).all(|j| self.column_satisfied(j));
+        }
+
+        self.cells
+            .iter()
+            .zip(self.puzzle.completed_grid.iter())
+            .all(|(&cell, &expected)| (cell == CellState::Filled) == (expected == 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CellState, Hint, Progress};
+    use crate::{Line, Nonogram};
+    use ndarray::arr2;
+
+    fn puzzle() -> Nonogram {
+        Nonogram::from_grid(arr2(&[[1, 1, 0, 1, 0], [0, 0, 0, 0, 0], [1, 0, 1, 1, 1]]))
+    }
+
+    #[test]
+    fn undo_and_redo_moves() {
+        let mut progress = Progress::new(puzzle());
+
+        progress.set(0, 0, CellState::Filled);
+        progress.set(0, 1, CellState::Empty);
+
+        assert_eq!(progress.undo().unwrap().column, 1);
+        assert_eq!(progress.get(0, 1), Some(CellState::Unknown));
+        assert_eq!(progress.redo().unwrap().after, CellState::Empty);
+        assert_eq!(progress.get(0, 1), Some(CellState::Empty));
+
+        progress.undo();
+        progress.set(2, 2, CellState::Filled);
+
+        assert!(!progress.can_redo());
+    }
+
+    #[test]
+    fn toggle_clears_same_state() {
+        let mut progress = Progress::new(puzzle());
+
+        progress.toggle(1, 1, CellState::Empty);
+        progress.toggle(1, 1, CellState::Empty);
+
+        assert_eq!(progress.get(1, 1), Some(CellState::Unknown));
+        assert!(progress.set(7, 7, CellState::Filled).is_none());
+    }
+
+    #[test]
+    fn row_satisfied_by_filled_cells() {
+        let mut progress = Progress::new(puzzle());
+
+        progress.set(0, 0, CellState::Filled);
+        progress.set(0, 1, CellState::Filled);
+
+        assert!(!progress.row_satisfied(0));
+
+        progress.set(0, 3, CellState::Filled);
+
+        assert!(progress.row_satisfied(0));
+        assert!(progress.row_satisfied(1));
+        assert!(!progress.column_satisfied(0));
+    }
+
+    #[test]
+    fn completed_segments_from_both_ends() {
+        let mut progress = Progress::new(puzzle());
+
+        progress.set(2, 0, CellState::Filled);
+        progress.set(2, 1, CellState::Empty);
+
+        assert_eq!(progress.completed_row_segments(2), vec![true, false]);
+
+        progress.set(2, 2, CellState::Filled);
+        progress.set(2, 3, CellState::Filled);
+        progress.set(2, 4, CellState::Filled);
+
+        assert_eq!(progress.completed_row_segments(2), vec![true, true]);
+    }
+
+    #[test]
+    fn completed_segments_need_closed_runs() {
+        let mut progress = Progress::new(puzzle());
+
+        progress.set(2, 3, CellState::Filled);
+        progress.set(2, 4, CellState::Filled);
+
+        assert_eq!(progress.completed_row_segments(2), vec![false, false]);
+    }
+
+    #[test]
+    fn mistakes_against_completed_grid() {
+        let mut progress = Progress::new(puzzle());
+
+        progress.set(0, 0, CellState::Empty);
+        progress.set(1, 1, CellState::Filled);
+        progress.set(2, 0, CellState::Filled);
+
+        assert_eq!(progress.mistakes(), vec![(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn complete_when_filled_cells_match() {
+        let mut progress = Progress::new(puzzle());
+
+        for &(i, j) in &[(0, 0), (0, 1), (0, 3), (2, 0), (2, 2), (2, 3), (2, 4)] {
+            progress.set(i, j, CellState::Filled);
+        }
+
+        assert!(progress.is_complete());
+    }
+
+    #[test]
+    fn clue_only_complete_when_lines_satisfied() {
+        let original = puzzle();
+        let clues = Nonogram::from_clues(original.row_segments, original.column_segments);
+        let mut progress = Progress::new(clues);
+
+        for &(i, j) in &[(0, 0), (0, 1), (0, 3), (2, 0), (2, 2), (2, 3)] {
+            progress.set(i, j, CellState::Filled);
+        }
+
+        assert!(!progress.is_complete());
+
+        progress.set(2, 4, CellState::Filled);
+
+        assert!(progress.is_complete());
+        assert!(progress.mistakes().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn save_and_resume() {
+        let mut progress = Progress::new(puzzle());
+
+        progress.set(0, 0, CellState::Filled);
+        progress.set(1, 0, CellState::Empty);
+        progress.undo();
+
+        let saved = serde_json::to_string(&progress).unwrap();
+        let mut resumed: Progress = serde_json::from_str(&saved).unwrap();
+
+        assert_eq!(resumed.cells(), progress.cells());
+        assert_eq!(resumed.redo().unwrap().row, 1);
+    }
+
+    #[test]
+    fn hints_fix_mistakes_first() {
+        let mut progress = Progress::new(puzzle());
+
+        progress.set(1, 2, CellState::Filled);
+
+        assert_eq!(progress.hint(), Some(Hint::Mistake { row: 1, column: 2 }));
+    }
+
+    #[test]
+    fn hints_use_the_simplest_rule() {
+        let mut progress = Progress::new(puzzle());
+
+        assert_eq!(
+            progress.hint(),
+            Some(Hint::Deduction {
+                row: 0,
+                column: 1,
+                state: CellState::Filled,
+                line: Line::Row(0),
+                rule: "simple overlap",
+            })
+        );
+
+        for (index, &value) in puzzle().completed_grid.indexed_iter() {
+            let state = if value == 1 {
+                CellState::Filled
+            } else {
+                CellState::Empty
+            };
+
+            progress.set(index.0, index.1, state);
+        }
+
+        assert_eq!(progress.hint(), None);
+    }
+
+    #[test]
+    fn hints_reveal_when_no_line_decides() {
+        let progress = Progress::new(Nonogram::from_grid(arr2(&[[1, 0], [0, 1]])));
+
+        assert_eq!(
+            progress.hint(),
+            Some(Hint::Reveal {
+                row: 0,
+                column: 0,
+                state: CellState::Filled,
+            })
+        );
+    }
+}
@@ -0,0 +1,389 @@
+use super::budget::Budget;
+use super::prelude::*;
+use super::solution::{enumerate_row_states, filter_invalid_row_states, Execution, StateRow};
+use super::{CellState, Line, Nonogram, SolveOptions, SolveStats, SolveStatus};
+use alloc::collections::BTreeSet;
+use core::mem;
+use ndarray::Array2;
+
+/// Most dead ends remembered by one search, so memory stays bounded on huge searches.
+const MAX_FAILED_STATES: usize = 100_000;
+
+/// Every solution of a puzzle found by `Nonogram::solutions`, up to the requested number.
+#[derive(Clone, Debug)]
+pub struct Solutions {
+    /// Solutions as grids of 0s and 1s, in the order the search found them.
+    pub grids: Vec<Array2<u8>>,
+    /// Whether the search ran to the end, so `grids` holds every solution there is.
+    pub complete: bool,
+    /// Work done across the whole search. `probes` counts the line placements tried and
+    /// `backtracks` the branches that failed.
+    pub stats: SolveStats,
+}
+
+impl Solutions {
+    /// Whether the clues have exactly one solution.
+    pub fn is_unique(&self) -> bool {
+        self.complete && self.grids.len() == 1
+    }
+}
+
+/// Solutions found by guessing where line propagation stalls.
+pub(crate) struct Search {
+    pub(crate) solutions: Vec<Array2<CellState>>,
+    /// Whether every branch was explored, so `solutions` holds every solution there is.
+    pub(crate) complete: bool,
+    pub(crate) stats: SolveStats,
+    limit: usize,
+    /// Set once the limit or the budget cuts the search short.
+    stopped: bool,
+    /// Grids that propagation stalled on and whose every branch turned out to be a dead end.
+    failed: BTreeSet<Vec<CellState>>,
+}
+
+/// Looks for up to `limit` solutions of the puzzle.
+pub(crate) fn find_solutions(puzzle: &Nonogram, limit: usize) -> Search {
+    search(puzzle, limit, &SolveOptions::default())
+}
+
+/// Depth-first search over whole line placements.
+///
+/// Propagates the lines, then picks the undecided line with the fewest candidates left and
+/// tries each of them in turn. Crossing lines are pruned against the chosen placement before
+/// recursing, and any stalled grid whose branches all failed is remembered so it isn't explored
+/// a second time. A budget that runs out leaves the search incomplete.
+pub(crate) fn search(puzzle: &Nonogram, limit: usize, options: &SolveOptions) -> Search {
+    let budget = Budget::start(options);
+    let execution = Execution::of(options);
+    let mut search = Search {
+        solutions: Vec::new(),
+        complete: true,
+        stats: SolveStats::default(),
+        limit,
+        stopped: false,
+        failed: BTreeSet::new(),
+    };
+    let possibilities = execution
+        .enumerate(puzzle.width(), &puzzle.row_segments, &budget)
+        .and_then(|rows| {
+            execution
+                .enumerate(puzzle.height(), &puzzle.column_segments, &budget)
+                .map(|columns| (rows, columns))
+        });
+
+    match possibilities {
+        Some((rows, columns)) => {
+            search.stats.record_candidates(&rows, &columns, true);
+            branch(puzzle, rows, columns, &budget, execution, &mut search);
+        }
+        None => search.complete = false,
+    }
+
+    search.stats.elapsed = budget.elapsed();
+    search
+}
+
+/// Explores everything below one set of candidates and returns how many solutions it found.
+fn branch(
+    puzzle: &Nonogram,
+    mut rows: Vec<Vec<StateRow>>,
+    mut columns: Vec<Vec<StateRow>>,
+    budget: &Budget,
+    execution: Execution,
+    search: &mut Search,
+) -> usize {
+    if search.solutions.len() >= search.limit || budget.interrupted() {
+        search.complete = false;
+        search.stopped = true;
+        return 0;
+    }
+
+    let stats = mem::take(&mut search.stats);
+    let (status, grid, stats) = puzzle.propagate(&mut rows, &mut columns, budget, execution, stats);
+    let grid = grid.into_array();
+
+    search.stats = stats;
+
+    match status {
+        SolveStatus::Solved => {
+            search.solutions.push(grid);
+            1
+        }
+        SolveStatus::Contradiction => {
+            search.stats.backtracks += 1;
+            0
+        }
+        SolveStatus::BudgetExceeded => {
+            search.complete = false;
+            search.stopped = true;
+            0
+        }
+        SolveStatus::Stalled => {
+            let key: Vec<CellState> = grid.iter().cloned().collect();
+
+            if search.failed.contains(&key) {
+                search.stats.backtracks += 1;
+                return 0;
+            }
+
+            let line = most_constrained(&rows, &columns);
+            let candidates = match line {
+                Line::Row(i) => rows[i].clone(),
+                Line::Column(j) => columns[j].clone(),
+            };
+            let mut found = 0;
+
+            for candidate in candidates {
+                if search.stopped {
+                    break;
+                }
+
+                let mut rows = rows.clone();
+                let mut columns = columns.clone();
+
+                search.stats.probes += 1;
+
+                if place(&grid, line, candidate, &mut rows, &mut columns) {
+                    found += branch(puzzle, rows, columns, budget, execution, search);
+                } else {
+                    search.stats.backtracks += 1;
+                }
+            }
+
+            if found == 0 && !search.stopped && search.failed.len() < MAX_FAILED_STATES {
+                search.failed.insert(key);
+            }
+
+            found
+        }
+    }
+}
+
+/// The undecided line with the fewest candidates, which keeps the search tree narrow.
+fn most_constrained(rows: &[Vec<StateRow>], columns: &[Vec<StateRow>]) -> Line {
+    let rows = rows
+        .iter()
+        .enumerate()
+        .map(|(i, candidates)| (candidates.len(), Line::Row(i)));
+    let columns = columns
+        .iter()
+        .enumerate()
+        .map(|(j, candidates)| (candidates.len(), Line::Column(j)));
+
+    // Propagation only stalls with some cell unknown, and that cell's row has several candidates.
+    rows.chain(columns)
+        .filter(|&(count, _)| count > 1)
+        .min_by_key(|&(count, _)| count)
+        .map(|(_, line)| line)
+        .unwrap()
+}
+
+/// Commits `line` to one placement and prunes every crossing line against it. Returns `false`
+/// when some crossing line has no candidates left.
+fn place(
+    grid: &Array2<CellState>,
+    line: Line,
+    placement: StateRow,
+    rows: &mut [Vec<StateRow>],
+    columns: &mut [Vec<StateRow>],
+) -> bool {
+    let (crossing, index) = match line {
+        Line::Row(i) => (&mut *columns, i),
+        Line::Column(j) => (&mut *rows, j),
+    };
+
+    for (position, candidates) in crossing.iter_mut().enumerate() {
+        let mut known = match line {
+            Line::Row(_) => StateRow(grid.column(position).to_owned()),
+            Line::Column(_) => StateRow(grid.row(position).to_owned()),
+        };
+
+        known.0[index] = placement.0[position];
+        *candidates = filter_invalid_row_states(&known, candidates);
+
+        if candidates.is_empty() {
+            return false;
+        }
+    }
+
+    match line {
+        Line::Row(i) => rows[i] = vec![placement],
+        Line::Column(j) => columns[j] = vec![placement],
+    }
+
+    true
+}
+
+impl Nonogram {
+    /// Finds up to `max_solutions` solutions of the clues, searching wherever line logic stalls.
+    ///
+    /// Asking for two solutions is enough to tell whether a puzzle is unique.
+    pub fn solutions(&self, max_solutions: usize) -> Solutions {
+        self.solutions_with_options(max_solutions, &SolveOptions::default())
+    }
+
+    /// `solutions`, stopping early when `options` runs out of time or passes or is cancelled.
+    ///
+    /// `max_passes` counts propagation passes over the whole search, not per branch. A search
+    /// cut short reports itself incomplete along with whatever solutions it found.
+    pub fn solutions_with_options(
+        &self,
+        max_solutions: usize,
+        options: &SolveOptions,
+    ) -> Solutions {
+        let search = search(self, max_solutions, options);
+
+        Solutions {
+            grids: search
+                .solutions
+                .iter()
+                .map(|grid| grid.map(|&cell| (cell == CellState::Filled) as u8))
+                .collect(),
+            complete: search.complete,
+            stats: search.stats,
+        }
+    }
+}
+
+/// Line propagation that, when it stalls, tries each unknown cell both ways and keeps whichever
+/// state doesn't lead straight to a contradiction. Never guesses more than one cell deep.
+///
+/// Returns how far it got, with the work of every propagation and probe counted in the stats.
+/// `max_passes` counts the passes of every propagation, probes included.
+pub(crate) fn probe(puzzle: &Nonogram, options: &SolveOptions) -> (SolveStatus, SolveStats) {
+    let budget = Budget::start(options);
+    let mut stats = SolveStats::default();
+    let possibilities = Execution::Sequential
+        .enumerate(puzzle.width(), &puzzle.row_segments, &budget)
+        .and_then(|rows| {
+            Execution::Sequential
+                .enumerate(puzzle.height(), &puzzle.column_segments, &budget)
+                .map(|columns| (rows, columns))
+        });
+    let (mut rows, mut columns) = match possibilities {
+        Some(possibilities) => possibilities,
+        None => return (SolveStatus::BudgetExceeded, stats),
+    };
+
+    stats.record_candidates(&rows, &columns, true);
+
+    let status = 'propagate: loop {
+        let (status, grid, updated) = puzzle.propagate(
+            &mut rows,
+            &mut columns,
+            &budget,
+            Execution::Sequential,
+            mem::take(&mut stats),
+        );
+
+        stats = updated;
+
+        if status != SolveStatus::Stalled {
+            break status;
+        }
+
+        let unknown: Vec<(usize, usize)> = grid
+            .into_array()
+            .indexed_iter()
+            .filter(|&(_, &cell)| cell == CellState::Unknown)
+            .map(|(index, _)| index)
+            .collect();
+
+        for (i, j) in unknown {
+            for &(state, opposite) in &[
+                (CellState::Filled, CellState::Empty),
+                (CellState::Empty, CellState::Filled),
+            ] {
+                let mut probed_rows = rows.clone();
+                let mut probed_columns = columns.clone();
+
+                stats.probes += 1;
+                fix(&mut probed_rows, &mut probed_columns, (i, j), state);
+
+                let (status, _, updated) = puzzle.propagate(
+                    &mut probed_rows,
+                    &mut probed_columns,
+                    &budget,
+                    Execution::Sequential,
+                    mem::take(&mut stats),
+                );
+
+                stats = updated;
+
+                match status {
+                    SolveStatus::Contradiction => {
+                        fix(&mut rows, &mut columns, (i, j), opposite);
+                        continue 'propagate;
+                    }
+                    SolveStatus::BudgetExceeded => break 'propagate status,
+                    _ => (),
+                }
+            }
+        }
+
+        break SolveStatus::Stalled;
+    };
+
+    stats.record_candidates(&rows, &columns, false);
+    stats.elapsed = budget.elapsed();
+
+    (status, stats)
+}
+
+/// Every placement of every row and column clue.
+pub(crate) fn enumerate(puzzle: &Nonogram) -> (Vec<Vec<StateRow>>, Vec<Vec<StateRow>>) {
+    let rows = puzzle
+        .row_segments
+        .iter()
+        .map(|clue| enumerate_row_states(puzzle.width(), clue))
+        .collect();
+    let columns = puzzle
+        .column_segments
+        .iter()
+        .map(|clue| enumerate_row_states(puzzle.height(), clue))
+        .collect();
+
+    (rows, columns)
+}
+
+/// Drops the candidates of the cell's row and column that don't give it `state`.
+pub(crate) fn fix(
+    rows: &mut [Vec<StateRow>],
+    columns: &mut [Vec<StateRow>],
+    (i, j): (usize, usize),
+    state: CellState,
+) {
+    rows[i].retain(|row| row.0[j] == state);
+    columns[j].retain(|column| column.0[i] == state);
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::probe;
+    use crate::{Difficulty, GenerateOptions, Nonogram, SolveOptions, SolveStatus};
+
+    #[test]
+    fn probing_counts_its_probes() {
+        let options = GenerateOptions {
+            seed: Some(11),
+            difficulty: Some(Difficulty::Hard..=Difficulty::Hard),
+            ..GenerateOptions::new(8, 8)
+        };
+        let puzzle = Nonogram::generate_with(&options).unwrap();
+        let (status, stats) = probe(&puzzle, &SolveOptions::default());
+
+        assert_eq!(puzzle.solve().status, SolveStatus::Stalled);
+        assert_eq!(status, SolveStatus::Solved);
+        assert!(stats.probes > 0);
+        assert!(stats.passes > 1);
+        assert_eq!(stats.row_candidates.len(), 8);
+        assert_eq!(stats.backtracks, 0);
+
+        let options = SolveOptions {
+            max_passes: Some(stats.passes - 1),
+            ..SolveOptions::default()
+        };
+
+        assert_eq!(probe(&puzzle, &options).0, SolveStatus::BudgetExceeded);
+    }
+}
@@ -0,0 +1,492 @@
+use super::budget::Budget;
+use super::prelude::*;
+use super::solution::{enumerate_row_states, filter_invalid_row_states, Execution, StateRow};
+use super::{
+    build_clue, CellState, Line, LineCandidates, Nonogram, SolveOptions, SolveResult, SolveStats,
+    SolveStatus,
+};
+use alloc::collections::VecDeque;
+use core::mem;
+use ndarray::Array2;
+
+/// Keeps a puzzle's solver state around between edits to its picture.
+///
+/// Enumerating every placement of every clue is the expensive part of solving. A session does it
+/// once up front and afterwards only re-enumerates the row and column whose clues an edit
+/// changed. It also keeps the candidates propagation narrowed and the cells it settled. After an
+/// edit only the changed lines, and the lines that relied on cells they settled, go back to
+/// their full placements, and only lines whose cells change are narrowed again. This makes it
+/// cheap to ask whether a picture is still uniquely solvable after every click in an editor.
+///
+/// Lines are narrowed one at a time from a queue rather than in passes, so a session's stats
+/// count the whole re-solve as a single pass.
+#[derive(Clone, Debug)]
+pub struct SolveSession {
+    puzzle: Nonogram,
+    rows: Vec<LineState>,
+    columns: Vec<LineState>,
+    /// The cells settled so far.
+    grid: Array2<CellState>,
+    /// The line whose candidates settled each known cell.
+    settled_by: Array2<Option<Line>>,
+    result: SolveResult,
+}
+
+/// What a session knows about one row or column.
+#[derive(Clone, Debug)]
+struct LineState {
+    /// Every placement of the line's clue.
+    placements: Vec<StateRow>,
+    /// The placements still consistent with the settled cells.
+    candidates: Vec<StateRow>,
+    /// The clue the line had before its last change, with its placements, so that undoing an
+    /// edit doesn't enumerate them again.
+    previous: Option<(Vec<usize>, Vec<StateRow>)>,
+    /// Whether the clue changed since the line was last narrowed.
+    changed: bool,
+}
+
+impl LineState {
+    fn new(size: usize, clue: &[usize]) -> LineState {
+        let placements = enumerate_row_states(size, clue);
+
+        LineState {
+            candidates: placements.clone(),
+            placements,
+            previous: None,
+            changed: true,
+        }
+    }
+
+    /// Switches the line from the clue `old` to `clue`.
+    fn set_clue(&mut self, size: usize, old: Vec<usize>, clue: &[usize]) {
+        let placements = match self.previous.take() {
+            Some((previous, placements)) if previous == clue => placements,
+            _ => enumerate_row_states(size, clue),
+        };
+
+        self.previous = Some((old, mem::replace(&mut self.placements, placements)));
+        self.changed = true;
+    }
+}
+
+/// One flag per row and column.
+struct LineFlags {
+    rows: Vec<bool>,
+    columns: Vec<bool>,
+}
+
+impl LineFlags {
+    fn new(height: usize, width: usize) -> LineFlags {
+        LineFlags {
+            rows: vec![false; height],
+            columns: vec![false; width],
+        }
+    }
+
+    fn get(&mut self, line: Line) -> &mut bool {
+        match line {
+            Line::Row(i) => &mut self.rows[i],
+            Line::Column(j) => &mut self.columns[j],
+        }
+    }
+
+    /// Sets the line's flag, returning whether it was clear before.
+    fn insert(&mut self, line: Line) -> bool {
+        !mem::replace(self.get(line), true)
+    }
+}
+
+/// The cell at `position` along `line` and the line crossing it there.
+fn crossing(line: Line, position: usize) -> ((usize, usize), Line) {
+    match line {
+        Line::Row(i) => ((i, position), Line::Column(position)),
+        Line::Column(j) => ((position, j), Line::Row(position)),
+    }
+}
+
+impl SolveSession {
+    /// Enumerates every line of `puzzle` and solves it.
+    pub fn new(puzzle: Nonogram) -> SolveSession {
+        let (height, width) = (puzzle.height(), puzzle.width());
+        let rows = puzzle
+            .row_segments
+            .iter()
+            .map(|clue| LineState::new(width, clue))
+            .collect();
+        let columns = puzzle
+            .column_segments
+            .iter()
+            .map(|clue| LineState::new(height, clue))
+            .collect();
+        let mut session = SolveSession {
+            puzzle,
+            rows,
+            columns,
+            grid: Array2::default((height, width)),
+            settled_by: Array2::default((height, width)),
+            result: SolveResult {
+                status: SolveStatus::Stalled,
+                grid: Array2::default((height, width)),
+                stats: SolveStats::default(),
+            },
+        };
+
+        session.solve();
+        session
+    }
+
+    pub fn puzzle(&self) -> &Nonogram {
+        &self.puzzle
+    }
+
+    pub fn into_puzzle(self) -> Nonogram {
+        self.puzzle
+    }
+
+    /// The solver's outcome for the puzzle as it stands.
+    pub fn result(&self) -> &SolveResult {
+        &self.result
+    }
+
+    pub fn status(&self) -> SolveStatus {
+        self.result.status
+    }
+
+    /// Fills or clears one cell of the picture, updates the clues of its row and column and
+    /// solves again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell lies outside the picture, which includes every cell of a clue-only
+    /// puzzle.
+    pub fn set_cell(&mut self, row: usize, column: usize, filled: bool) -> SolveStatus {
+        self.set_cells(&[((row, column), filled)])
+    }
+
+    /// Applies several edits to the picture before solving again once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any cell lies outside the picture.
+    pub fn set_cells(&mut self, cells: &[((usize, usize), bool)]) -> SolveStatus {
+        self.edit(cells);
+        self.solve();
+        self.status()
+    }
+
+    /// Applies edits to the picture and its clues without solving, leaving `result` describing
+    /// the picture before them until the next solve.
+    pub(crate) fn edit(&mut self, cells: &[((usize, usize), bool)]) {
+        for &((row, column), filled) in cells {
+            let value = filled as u8;
+
+            if self.puzzle.completed_grid[[row, column]] != value {
+                self.puzzle.completed_grid[[row, column]] = value;
+                self.update_clues(row, column);
+            }
+        }
+    }
+
+    /// Rebuilds the clues through a cell and switches whichever of them changed.
+    fn update_clues(&mut self, row: usize, column: usize) {
+        let row_clue = build_clue(self.puzzle.completed_grid.row(row));
+        let column_clue = build_clue(self.puzzle.completed_grid.column(column));
+
+        if row_clue != self.puzzle.row_segments[row] {
+            let old = mem::replace(&mut self.puzzle.row_segments[row], row_clue);
+
+            self.rows[row].set_clue(self.puzzle.width(), old, &self.puzzle.row_segments[row]);
+        }
+
+        if column_clue != self.puzzle.column_segments[column] {
+            let old = mem::replace(&mut self.puzzle.column_segments[column], column_clue);
+
+            self.columns[column].set_clue(
+                self.puzzle.height(),
+                old,
+                &self.puzzle.column_segments[column],
+            );
+        }
+    }
+
+    /// Flips one cell of the picture and solves again.
+    pub fn toggle_cell(&mut self, row: usize, column: usize) -> SolveStatus {
+        let filled = self.puzzle.completed_grid[[row, column]] == 1;
+
+        self.set_cell(row, column, !filled)
+    }
+
+    fn line(&mut self, line: Line) -> &mut LineState {
+        match line {
+            Line::Row(i) => &mut self.rows[i],
+            Line::Column(j) => &mut self.columns[j],
+        }
+    }
+
+    fn lines(&self) -> impl Iterator<Item = Line> {
+        (0..self.rows.len())
+            .map(Line::Row)
+            .chain((0..self.columns.len()).map(Line::Column))
+    }
+
+    fn size(&self, line: Line) -> usize {
+        match line {
+            Line::Row(_) => self.columns.len(),
+            Line::Column(_) => self.rows.len(),
+        }
+    }
+
+    /// Narrows the candidates again after the clues of some lines changed.
+    ///
+    /// Cells a changed line settled may no longer hold, and neither may anything the lines
+    /// crossing them deduced from them. So every line reachable that way goes back to its full
+    /// placements and loses the cells it settled. Everything else was deduced from unchanged
+    /// clues alone and stays. The reset lines are then narrowed from a queue, and any line that
+    /// gains a settled cell is queued again until nothing changes.
+    fn solve(&mut self) {
+        let options = SolveOptions::default();
+        let budget = Budget::start(&options);
+        let (height, width) = (self.rows.len(), self.columns.len());
+        let mut reset = LineFlags::new(height, width);
+        let mut queue: VecDeque<Line> = VecDeque::new();
+
+        for line in self.lines().collect::<Vec<_>>() {
+            if mem::replace(&mut self.line(line).changed, false) && reset.insert(line) {
+                queue.push_back(line);
+            }
+        }
+
+        if queue.is_empty() {
+            return;
+        }
+
+        // Everything queued so far is reset, so the queue doubles as the list to walk.
+        let mut next = 0;
+
+        while next < queue.len() {
+            let line = queue[next];
+
+            for position in 0..self.size(line) {
+                let (cell, crossed) = crossing(line, position);
+
+                if self.settled_by[cell] == Some(line) {
+                    self.settled_by[cell] = None;
+                    self.grid[cell] = CellState::Unknown;
+
+                    if reset.insert(crossed) {
+                        queue.push_back(crossed);
+                    }
+                }
+            }
+
+            let state = self.line(line);
+
+            state.candidates = state.placements.clone();
+            next += 1;
+        }
+
+        let mut queued = reset;
+        let mut stats = SolveStats {
+            passes: 1,
+            ..SolveStats::default()
+        };
+        let mut cells = 0;
+
+        while let Some(line) = queue.pop_front() {
+            *queued.get(line) = false;
+            stats.lines_processed += 1;
+
+            let known = StateRow(match line {
+                Line::Row(i) => self.grid.row(i).to_owned(),
+                Line::Column(j) => self.grid.column(j).to_owned(),
+            });
+            let state = self.line(line);
+
+            state.candidates = filter_invalid_row_states(&known, &state.candidates);
+
+            let settled: Vec<(usize, CellState)> = match state.candidates.split_first() {
+                Some((first, rest)) => (0..known.0.len())
+                    .filter(|&position| known.0[position] == CellState::Unknown)
+                    .map(|position| (position, first.0[position]))
+                    .filter(|&(position, cell)| rest.iter().all(|other| other.0[position] == cell))
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            for (position, cell) in settled {
+                let (index, crossed) = crossing(line, position);
+
+                self.grid[index] = cell;
+                self.settled_by[index] = Some(line);
+                cells += 1;
+
+                if queued.insert(crossed) {
+                    queue.push_back(crossed);
+                }
+            }
+        }
+
+        let lines = |states: &[LineState]| {
+            states
+                .iter()
+                .map(|state| LineCandidates {
+                    initial: state.placements.len(),
+                    remaining: state.candidates.len(),
+                })
+                .collect()
+        };
+
+        stats.row_candidates = lines(&self.rows);
+        stats.column_candidates = lines(&self.columns);
+        stats.cells_per_pass.push(cells);
+        stats.elapsed = budget.elapsed();
+
+        let contradiction = self
+            .rows
+            .iter()
+            .chain(&self.columns)
+            .any(|state| state.candidates.is_empty());
+
+        // Every line was narrowed against its final cells, so a complete grid matches every clue.
+        let status = if contradiction {
+            SolveStatus::Contradiction
+        } else if self.grid.iter().all(|&cell| cell != CellState::Unknown) {
+            SolveStatus::Solved
+        } else {
+            SolveStatus::Stalled
+        };
+
+        self.result = SolveResult {
+            status,
+            grid: self.grid.clone(),
+            stats,
+        };
+    }
+}
+
+/// Propagates from copies of the cached candidates, leaving the cache as it was.
+pub(crate) fn solve_from(
+    puzzle: &Nonogram,
+    rows: &[Vec<StateRow>],
+    columns: &[Vec<StateRow>],
+) -> SolveResult {
+    let options = SolveOptions::default();
+    let budget = Budget::start(&options);
+    let mut rows = rows.to_vec();
+    let mut columns = columns.to_vec();
+    let mut stats = SolveStats::default();
+
+    stats.record_candidates(&rows, &columns, true);
+
+    let (status, grid, mut stats) = puzzle.propagate(
+        &mut rows,
+        &mut columns,
+        &budget,
+        Execution::Sequential,
+        stats,
+    );
+
+    stats.elapsed = budget.elapsed();
+
+    SolveResult {
+        status,
+        grid: grid.into_array(),
+        stats,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SolveSession;
+    #[cfg(feature = "std")]
+    use crate::GenerateOptions;
+    use crate::{CellState, Nonogram, SolveStatus};
+    use ndarray::{arr2, Array2};
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn session_matches_fresh_solve_after_edits() {
+        let options = GenerateOptions {
+            seed: Some(9),
+            ..GenerateOptions::new(7, 6)
+        };
+        let mut session = SolveSession::new(Nonogram::generate_with(&options).unwrap());
+
+        for &(row, column) in &[(0, 0), (3, 4), (5, 6), (3, 4), (2, 2), (0, 6)] {
+            let status = session.toggle_cell(row, column);
+            let fresh = Nonogram::from_grid(session.puzzle().completed_grid.clone());
+            let expected = fresh.solve();
+
+            assert_eq!(session.puzzle().row_segments, fresh.row_segments);
+            assert_eq!(session.puzzle().column_segments, fresh.column_segments);
+            assert_eq!(status, expected.status);
+            assert_eq!(session.result().grid, expected.grid);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn batched_and_deferred_edits_match_fresh_solves() {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(4);
+
+        for seed in 0..4 {
+            let options = GenerateOptions {
+                seed: Some(seed),
+                ..GenerateOptions::new(9, 8)
+            };
+            let mut session = SolveSession::new(Nonogram::generate_with(&options).unwrap());
+
+            for round in 0..12 {
+                let mut cell = || ((rng.gen_range(0, 8), rng.gen_range(0, 9)), rng.gen());
+                let edits = [cell(), cell(), cell()];
+
+                if round % 3 == 0 {
+                    session.edit(&edits[..2]);
+                    session.set_cell(edits[2].0 .0, edits[2].0 .1, edits[2].1);
+                } else {
+                    session.set_cells(&edits);
+                }
+
+                let expected = Nonogram::from_grid(session.puzzle().completed_grid.clone()).solve();
+
+                assert_eq!(session.status(), expected.status);
+                assert_eq!(session.result().grid, expected.grid);
+            }
+        }
+    }
+
+    #[test]
+    fn edits_only_narrow_the_lines_they_affect() {
+        // The rows settle every cell of the blank picture, so filling a cell in the top row only
+        // unsettles that row, and the columns through it have to be narrowed again. The other
+        // rows keep their candidates.
+        let mut session = SolveSession::new(Nonogram::from_grid(Array2::zeros((4, 4))));
+
+        assert_eq!(session.set_cell(0, 0, true), SolveStatus::Solved);
+        assert!(session.result().stats.lines_processed < 8);
+        assert_eq!(session.result().grid, expected_grid(&session));
+    }
+
+    fn expected_grid(session: &SolveSession) -> Array2<CellState> {
+        session.puzzle().completed_grid.map(|&cell| {
+            if cell == 1 {
+                CellState::Filled
+            } else {
+                CellState::Empty
+            }
+        })
+    }
+
+    #[test]
+    fn edits_can_make_a_picture_unique() {
+        let mut session = SolveSession::new(Nonogram::from_grid(arr2(&[[1, 0], [0, 1]])));
+
+        assert_eq!(session.status(), SolveStatus::Stalled);
+        assert_eq!(session.set_cell(0, 1, true), SolveStatus::Solved);
+        assert_eq!(session.set_cell(0, 1, true), SolveStatus::Solved);
+        assert_eq!(session.puzzle().row_segments[0], vec![2]);
+    }
+}
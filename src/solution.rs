@@ -1,7 +1,11 @@
 extern crate ndarray;
 
-use ndarray::Array1;
-use std::collections::{HashSet, VecDeque};
+use crate::budget::{Budget, SolveOptions};
+use crate::placement::{count_placements, placements};
+use crate::prelude::*;
+use core::time::Duration;
+use ndarray::{Array1, Array2};
+use serde::{Deserialize, Serialize};
 
 #[path = "state_grid.rs"]
 mod state_grid;
@@ -11,40 +15,135 @@ mod state_row;
 pub use state_grid::StateGrid;
 pub use state_row::StateRow;
 
-#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+#[derive(
+    PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Hash, Default, Serialize, Deserialize,
+)]
 pub enum CellState {
+    #[default]
     Unknown,
     Empty,
     Filled,
 }
 
-impl Default for CellState {
-    fn default() -> Self {
-        CellState::Unknown
-    }
+/// How far the solver got with a puzzle.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum SolveStatus {
+    /// Every cell was determined.
+    Solved,
+    /// Line logic ran out of deductions while some cells were still unknown.
+    Stalled,
+    /// The clues of at least one line cannot be satisfied.
+    Contradiction,
+    /// The solver hit one of the limits in its `SolveOptions`, or was cancelled, before it
+    /// could finish.
+    BudgetExceeded,
 }
 
-pub fn enumerate_row_states(size: usize, clue: &[usize]) -> Vec<StateRow> {
-    let mut queue: VecDeque<StateRow> = VecDeque::new();
-    let mut known_rows: HashSet<StateRow> = HashSet::new();
+/// The outcome of running the solver on a puzzle.
+#[derive(Clone, Debug)]
+pub struct SolveResult {
+    pub status: SolveStatus,
+    /// The cells the solver determined. Anything it could not work out is left `Unknown`.
+    pub grid: Array2<CellState>,
+    pub stats: SolveStats,
+}
 
-    queue.push_back(StateRow(Array1::default(size)));
+/// How many placements one line's clue allowed.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub struct LineCandidates {
+    /// Placements enumerated before any propagation.
+    pub initial: usize,
+    /// Placements still consistent with the grid when the solver stopped.
+    pub remaining: usize,
+}
 
-    while let Some(row) = queue.pop_front() {
-        if row.is_known() {
-            known_rows.insert(row);
-        } else {
-            if let Some(seg_row) = row.new_w_appended_seg(clue) {
-                queue.push_front(seg_row);
-            }
+/// Counters gathered while solving, for seeing why a puzzle is slow or hard.
+#[derive(Clone, Debug, Default)]
+pub struct SolveStats {
+    /// Propagation passes over every row and column.
+    pub passes: usize,
+    /// Row and column propagations, counting each line once per pass.
+    pub lines_processed: usize,
+    /// Per row. Empty if the solver stopped before enumerating every line.
+    pub row_candidates: Vec<LineCandidates>,
+    /// Per column. Empty if the solver stopped before enumerating every line.
+    pub column_candidates: Vec<LineCandidates>,
+    /// Cells determined in each pass, in order.
+    pub cells_per_pass: Vec<usize>,
+    /// Tentative choices tested for a contradiction: single cells when probing, whole line
+    /// placements when searching. Line propagation never probes, so this stays at 0 for it.
+    pub probes: usize,
+    /// Tentative choices that had to be undone. Line propagation never backtracks, so this
+    /// stays at 0 for it.
+    pub backtracks: usize,
+    pub elapsed: Duration,
+}
 
-            if let Some(zero_row) = row.new_w_appended_zero(clue) {
-                queue.push_front(zero_row);
+impl SolveStats {
+    pub(crate) fn record_candidates(
+        &mut self,
+        rows: &[Vec<StateRow>],
+        columns: &[Vec<StateRow>],
+        initial: bool,
+    ) {
+        let update = |stats: &mut Vec<LineCandidates>, possibilities: &[Vec<StateRow>]| {
+            if initial {
+                *stats = possibilities
+                    .iter()
+                    .map(|p| LineCandidates {
+                        initial: p.len(),
+                        remaining: p.len(),
+                    })
+                    .collect();
+            } else {
+                for (line, p) in stats.iter_mut().zip(possibilities) {
+                    line.remaining = p.len();
+                }
             }
-        }
+        };
+
+        update(&mut self.row_candidates, rows);
+        update(&mut self.column_candidates, columns);
     }
 
-    known_rows.iter().cloned().collect()
+    /// Total candidates enumerated over every line.
+    pub fn initial_candidates(&self) -> usize {
+        self.row_candidates
+            .iter()
+            .chain(&self.column_candidates)
+            .map(|line| line.initial)
+            .sum()
+    }
+
+    /// Cells determined over every pass.
+    pub fn cells_resolved(&self) -> usize {
+        self.cells_per_pass.iter().sum()
+    }
+}
+
+pub fn enumerate_row_states(size: usize, clue: &[usize]) -> Vec<StateRow> {
+    enumerate_row_states_within(size, clue, usize::MAX).unwrap()
+}
+
+/// Enumerates the line's placements, giving up with `None` if there are more than `limit`.
+///
+/// The placements are counted before any are built, so a line over the limit costs nothing.
+pub fn enumerate_row_states_within(
+    size: usize,
+    clue: &[usize],
+    limit: usize,
+) -> Option<Vec<StateRow>> {
+    let blank = vec![CellState::Unknown; size];
+
+    if count_placements(clue, &blank) > limit as u128 {
+        return None;
+    }
+
+    Some(
+        placements(clue, &blank)
+            .map(|placed| StateRow(Array1::from(placed)))
+            .collect(),
+    )
 }
 
 pub fn filter_invalid_row_states(
@@ -53,17 +152,16 @@ pub fn filter_invalid_row_states(
 ) -> Vec<StateRow> {
     previous_states
         .iter()
-        .cloned()
         .filter(|state_row| {
             known_row
                 .0
                 .iter()
                 .zip(state_row.0.iter())
                 .all(|(known_cell, state_cell)| {
-                    *known_cell == CellState::Unknown
-                        || (*known_cell != CellState::Unknown && known_cell == state_cell)
+                    *known_cell == CellState::Unknown || known_cell == state_cell
                 })
         })
+        .cloned()
         .collect()
 }
 
@@ -94,10 +192,116 @@ pub fn common_row_indexes(states: &[StateRow]) -> Vec<(usize, CellState)> {
         .collect()
 }
 
+/// What one line learned from its remaining possibilities during a propagation pass.
+pub struct LineUpdate {
+    /// Unknown cells of the line that every possibility agrees on, by position in the line.
+    pub cells: Vec<(usize, CellState)>,
+    /// How many possibilities no longer fit the line.
+    pub removed: usize,
+    /// Whether the possibilities agree on a cell that is already known to be different.
+    pub contradiction: bool,
+}
+
+/// Fills in the cells all of a line's possibilities agree on, then drops the possibilities that
+/// no longer fit.
+///
+/// Only looks at the one line, so every row (or every column) can be propagated independently.
+pub fn propagate_line(line: &StateRow, possibilities: &mut Vec<StateRow>) -> LineUpdate {
+    let mut line = line.clone();
+    let mut cells = Vec::new();
+    let mut contradiction = false;
+
+    for (index, state) in common_row_indexes(possibilities) {
+        match line.state_at_index(index) {
+            Some(CellState::Unknown) => {
+                line.0[index] = state;
+                cells.push((index, state));
+            }
+            Some(current) if current != state => contradiction = true,
+            _ => (),
+        }
+    }
+
+    let filtered = filter_invalid_row_states(&line, possibilities);
+    let removed = possibilities.len() - filtered.len();
+
+    *possibilities = filtered;
+
+    LineUpdate {
+        cells,
+        removed,
+        contradiction,
+    }
+}
+
+/// Whether the solver works through lines one at a time or spreads them over a thread pool.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Execution {
+    Sequential,
+    #[cfg(feature = "parallel")]
+    Parallel,
+}
+
+impl Execution {
+    pub fn of(options: &SolveOptions) -> Execution {
+        #[cfg(feature = "parallel")]
+        if options.parallel {
+            return Execution::Parallel;
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        let _ = options;
+
+        Execution::Sequential
+    }
+
+    /// Enumerates every line's placements, or returns `None` if the budget ran out first.
+    pub fn enumerate(
+        self,
+        size: usize,
+        clues: &Array1<Vec<usize>>,
+        budget: &Budget,
+    ) -> Option<Vec<Vec<StateRow>>> {
+        match self {
+            Execution::Sequential => clues
+                .iter()
+                .map(|clue| enumerate_line(size, clue, budget))
+                .collect(),
+            #[cfg(feature = "parallel")]
+            Execution::Parallel => crate::parallel::enumerate(size, clues, budget),
+        }
+    }
+
+    pub fn propagate(
+        self,
+        lines: &[StateRow],
+        possibilities: &mut [Vec<StateRow>],
+    ) -> Vec<LineUpdate> {
+        match self {
+            Execution::Sequential => lines
+                .iter()
+                .zip(possibilities.iter_mut())
+                .map(|(line, possibilities)| propagate_line(line, possibilities))
+                .collect(),
+            #[cfg(feature = "parallel")]
+            Execution::Parallel => crate::parallel::propagate(lines, possibilities),
+        }
+    }
+}
+
+pub fn enumerate_line(size: usize, clue: &[usize], budget: &Budget) -> Option<Vec<StateRow>> {
+    if budget.interrupted() {
+        return None;
+    }
+
+    enumerate_row_states_within(size, clue, budget.line_candidates().unwrap_or(usize::MAX))
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        common_row_indexes, enumerate_row_states, filter_invalid_row_states, CellState, StateRow,
+        common_row_indexes, enumerate_row_states, filter_invalid_row_states, propagate_line,
+        CellState, StateRow,
     };
     use ndarray::arr1;
 
@@ -218,4 +422,36 @@ mod tests {
         assert_eq!(common_cells[4].0, 4);
         assert_eq!(common_cells[4].1, CellState::Filled);
     }
+
+    #[test]
+    fn propagate_line_fills_common_cells() {
+        let mut possibilities = enumerate_row_states(5, &[3]);
+        let line = StateRow(arr1(&[
+            CellState::Unknown,
+            CellState::Unknown,
+            CellState::Unknown,
+            CellState::Unknown,
+            CellState::Empty,
+        ]));
+        let update = propagate_line(&line, &mut possibilities);
+
+        assert_eq!(update.cells, vec![(2, CellState::Filled)]);
+        assert_eq!(update.removed, 1);
+        assert!(!update.contradiction);
+        assert_eq!(possibilities.len(), 2);
+    }
+
+    #[test]
+    fn propagate_line_reports_contradiction() {
+        let mut possibilities = enumerate_row_states(3, &[3]);
+        let line = StateRow(arr1(&[
+            CellState::Unknown,
+            CellState::Empty,
+            CellState::Unknown,
+        ]));
+        let update = propagate_line(&line, &mut possibilities);
+
+        assert!(update.contradiction);
+        assert_eq!(update.cells.len(), 2);
+    }
 }
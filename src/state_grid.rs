@@ -31,6 +31,10 @@ impl StateGrid {
     pub fn is_known(&self) -> bool {
         self.0.iter().all(|&cell| cell != CellState::Unknown)
     }
+
+    pub fn into_array(self) -> Array2<CellState> {
+        self.0
+    }
 }
 
 #[cfg(test)]
@@ -145,6 +149,19 @@ mod tests {
         assert!(!grid.is_known());
     }
 
+    #[test]
+    fn grid_into_array() {
+        let mut grid = StateGrid::new(2, 3);
+
+        grid.set(1, 2, CellState::Filled);
+
+        let array = grid.into_array();
+
+        assert_eq!(array.dim(), (2, 3));
+        assert_eq!(array[[1, 2]], CellState::Filled);
+        assert_eq!(array[[0, 0]], CellState::Unknown);
+    }
+
     #[test]
     fn grid_is_known_true() {
         let mut grid = StateGrid::new(2, 2);
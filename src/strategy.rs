@@ -0,0 +1,691 @@
+use super::budget::Budget;
+use super::placement::{block_fits, fits_table, placements};
+use super::prelude::*;
+use super::{CellState, Line, Nonogram, SolveOptions, SolveResult, SolveStats, SolveStatus};
+use alloc::collections::BTreeMap;
+use ndarray::Array2;
+
+/// A deduction rule that works on one row or column at a time.
+///
+/// Rules only have to be sound, not complete: returning fewer cells than could be determined is
+/// fine, since a later rule or a later pass can pick them up.
+pub trait LineStrategy {
+    /// Short name reported with every deduction the rule makes.
+    fn name(&self) -> &'static str;
+
+    /// Cells of `line` that must hold the given state for `clue` to be satisfied, by position in
+    /// the line.
+    ///
+    /// Returns `None` if the rule can tell that no placement of the clue fits the line.
+    fn deduce(&self, clue: &[usize], line: &[CellState]) -> Option<Vec<(usize, CellState)>>;
+}
+
+/// Blocks whose leftmost and rightmost packings overlap must cover the overlap.
+///
+/// Only looks at the clue and the line length, the first thing a person checks.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SimpleOverlap;
+
+/// Cells that no block can reach, judging by the clue and the line length alone, are empty.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SimpleSpaces;
+
+/// A filled cell too close to the edge for the first block to fit before it belongs to that
+/// block, so the block reaches at least as far from the edge as its length.
+///
+/// Crossed-out cells at the edge move the edge in. The same goes for the last block from the
+/// other end.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Glue;
+
+/// The other half of glue: the first block has to cover that filled cell, so cells further from
+/// it than the block's length, back towards the edge, are empty.
+///
+/// Named for the way mercury pulls back from the sides of its container.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Mercury;
+
+/// Fills the gap between two filled cells that only one block can reach, and crosses out a cell
+/// whose filling would join two filled runs into a block longer than any in the clue.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GapJoining;
+
+/// Slides every block as far left and as far right as the known cells allow, then fills the
+/// overlaps and crosses out cells no block can reach.
+///
+/// Anything glue, mercury or gap joining finds, this finds too.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EdgeForcing;
+
+/// Enumerates every placement of the clue that fits the line and keeps the cells they all agree
+/// on.
+///
+/// This finds everything that can be learned from a single line, so it makes a good last resort.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Enumeration;
+
+/// The built-in rules from simplest to most thorough, the order `solve_with_strategies` should
+/// try them in.
+pub fn default_strategies() -> Vec<Box<dyn LineStrategy>> {
+    vec![
+        Box::new(SimpleOverlap),
+        Box::new(SimpleSpaces),
+        Box::new(Glue),
+        Box::new(Mercury),
+        Box::new(GapJoining),
+        Box::new(EdgeForcing),
+        Box::new(Enumeration),
+    ]
+}
+
+/// The start of every block in the leftmost placement that fits the line.
+fn leftmost(clue: &[usize], line: &[CellState]) -> Option<Vec<usize>> {
+    let fits = fits_table(clue, line);
+
+    if !fits[0][0] {
+        return None;
+    }
+
+    let mut starts = Vec::with_capacity(clue.len());
+    let mut p = 0;
+
+    for k in 0..clue.len() {
+        let start = (p..line.len())
+            .find(|&s| block_fits(clue, line, &fits, k, s))
+            .unwrap();
+
+        starts.push(start);
+        p = start + clue[k] + 1;
+    }
+
+    Some(starts)
+}
+
+/// The start of every block in the rightmost placement that fits the line.
+fn rightmost(clue: &[usize], line: &[CellState]) -> Option<Vec<usize>> {
+    let reversed_clue: Vec<usize> = clue.iter().rev().cloned().collect();
+    let reversed_line: Vec<CellState> = line.iter().rev().cloned().collect();
+    let starts = leftmost(&reversed_clue, &reversed_line)?;
+
+    Some(
+        starts
+            .iter()
+            .zip(&reversed_clue)
+            .map(|(&start, &length)| line.len() - start - length)
+            .rev()
+            .collect(),
+    )
+}
+
+/// Overlaps of every block's extreme placements, followed by the cells outside every block's
+/// reach.
+fn left_right(
+    clue: &[usize],
+    line: &[CellState],
+    left: &[usize],
+    right: &[usize],
+    overlaps: bool,
+    spaces: bool,
+) -> Vec<(usize, CellState)> {
+    let mut cells = Vec::new();
+
+    if overlaps {
+        for (k, &length) in clue.iter().enumerate() {
+            cells.extend((right[k]..left[k] + length).map(|index| (index, CellState::Filled)));
+        }
+    }
+
+    if spaces {
+        let mut reachable = vec![false; line.len()];
+
+        for (k, &length) in clue.iter().enumerate() {
+            for cell in &mut reachable[left[k]..right[k] + length] {
+                *cell = true;
+            }
+        }
+
+        cells.extend(
+            (0..line.len())
+                .filter(|&index| !reachable[index])
+                .map(|index| (index, CellState::Empty)),
+        );
+    }
+
+    cells
+}
+
+/// Keeps the cells that are still unknown, or gives up if any contradicts a known cell.
+fn unknown_only(
+    line: &[CellState],
+    cells: Vec<(usize, CellState)>,
+) -> Option<Vec<(usize, CellState)>> {
+    let mut found = Vec::new();
+
+    for (index, state) in cells {
+        match line[index] {
+            CellState::Unknown => found.push((index, state)),
+            known if known != state => return None,
+            _ => (),
+        }
+    }
+
+    found.sort_by_key(|&(index, _)| index);
+    found.dedup();
+
+    Some(found)
+}
+
+/// Left-right deduction on a blank line, so only the clue and the line length count.
+fn clue_only(
+    clue: &[usize],
+    line: &[CellState],
+    overlaps: bool,
+    spaces: bool,
+) -> Option<Vec<(usize, CellState)>> {
+    let blank = vec![CellState::Unknown; line.len()];
+    let left = leftmost(clue, &blank)?;
+    let right = rightmost(clue, &blank)?;
+
+    unknown_only(
+        line,
+        left_right(clue, line, &left, &right, overlaps, spaces),
+    )
+}
+
+/// The first cell that isn't crossed out and the first filled cell, when the filled cell is too
+/// close to it for the first block to fit in between.
+fn first_block_anchor(clue: &[usize], line: &[CellState]) -> Option<(usize, usize)> {
+    let length = *clue.first()?;
+    let wall = line.iter().position(|&cell| cell != CellState::Empty)?;
+    let filled = line.iter().position(|&cell| cell == CellState::Filled)?;
+
+    if filled <= wall + length {
+        Some((wall, filled))
+    } else {
+        None
+    }
+}
+
+/// A deduction that only looks at the start of the line.
+type EdgeRule = fn(&[usize], &[CellState]) -> Vec<(usize, CellState)>;
+
+/// Runs a rule from the start of the line and again from the end.
+fn from_both_ends(clue: &[usize], line: &[CellState], rule: EdgeRule) -> Vec<(usize, CellState)> {
+    let reversed_clue: Vec<usize> = clue.iter().rev().cloned().collect();
+    let reversed_line: Vec<CellState> = line.iter().rev().cloned().collect();
+    let mut cells = rule(clue, line);
+
+    cells.extend(
+        rule(&reversed_clue, &reversed_line)
+            .into_iter()
+            .map(|(index, state)| (line.len() - 1 - index, state)),
+    );
+
+    cells
+}
+
+fn glue(clue: &[usize], line: &[CellState]) -> Vec<(usize, CellState)> {
+    match first_block_anchor(clue, line) {
+        Some((wall, filled)) => (filled + 1..wall + clue[0])
+            .map(|index| (index, CellState::Filled))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn mercury(clue: &[usize], line: &[CellState]) -> Vec<(usize, CellState)> {
+    match first_block_anchor(clue, line) {
+        Some((wall, filled)) => (wall..(filled + 1).saturating_sub(clue[0]))
+            .map(|index| (index, CellState::Empty))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+impl LineStrategy for SimpleOverlap {
+    fn name(&self) -> &'static str {
+        "simple overlap"
+    }
+
+    fn deduce(&self, clue: &[usize], line: &[CellState]) -> Option<Vec<(usize, CellState)>> {
+        clue_only(clue, line, true, false)
+    }
+}
+
+impl LineStrategy for SimpleSpaces {
+    fn name(&self) -> &'static str {
+        "simple spaces"
+    }
+
+    fn deduce(&self, clue: &[usize], line: &[CellState]) -> Option<Vec<(usize, CellState)>> {
+        clue_only(clue, line, false, true)
+    }
+}
+
+impl LineStrategy for Glue {
+    fn name(&self) -> &'static str {
+        "glue"
+    }
+
+    fn deduce(&self, clue: &[usize], line: &[CellState]) -> Option<Vec<(usize, CellState)>> {
+        unknown_only(line, from_both_ends(clue, line, glue))
+    }
+}
+
+impl LineStrategy for Mercury {
+    fn name(&self) -> &'static str {
+        "mercury"
+    }
+
+    fn deduce(&self, clue: &[usize], line: &[CellState]) -> Option<Vec<(usize, CellState)>> {
+        unknown_only(line, from_both_ends(clue, line, mercury))
+    }
+}
+
+impl LineStrategy for GapJoining {
+    fn name(&self) -> &'static str {
+        "gap joining"
+    }
+
+    fn deduce(&self, clue: &[usize], line: &[CellState]) -> Option<Vec<(usize, CellState)>> {
+        let left = leftmost(clue, line)?;
+        let right = rightmost(clue, line)?;
+        let only_block = |index: usize| {
+            let mut blocks =
+                (0..clue.len()).filter(|&k| left[k] <= index && index < right[k] + clue[k]);
+
+            match (blocks.next(), blocks.next()) {
+                (Some(k), None) => Some(k),
+                _ => None,
+            }
+        };
+        let filled: Vec<usize> = (0..line.len())
+            .filter(|&index| line[index] == CellState::Filled)
+            .collect();
+        let longest = clue.iter().max().cloned().unwrap_or(0);
+        let mut cells = Vec::new();
+
+        for pair in filled.windows(2) {
+            if let Some(k) = only_block(pair[0]) {
+                if only_block(pair[1]) == Some(k) {
+                    cells.extend((pair[0] + 1..pair[1]).map(|index| (index, CellState::Filled)));
+                }
+            }
+        }
+
+        for index in 0..line.len() {
+            let run = |cells: &mut dyn Iterator<Item = &CellState>| {
+                cells.take_while(|&&cell| cell == CellState::Filled).count()
+            };
+            let before = run(&mut line[..index].iter().rev());
+            let after = run(&mut line[index + 1..].iter());
+
+            if line[index] == CellState::Unknown
+                && before > 0
+                && after > 0
+                && before + 1 + after > longest
+            {
+                cells.push((index, CellState::Empty));
+            }
+        }
+
+        unknown_only(line, cells)
+    }
+}
+
+impl LineStrategy for EdgeForcing {
+    fn name(&self) -> &'static str {
+        "edge forcing"
+    }
+
+    fn deduce(&self, clue: &[usize], line: &[CellState]) -> Option<Vec<(usize, CellState)>> {
+        let left = leftmost(clue, line)?;
+        let right = rightmost(clue, line)?;
+
+        unknown_only(line, left_right(clue, line, &left, &right, true, true))
+    }
+}
+
+impl LineStrategy for Enumeration {
+    fn name(&self) -> &'static str {
+        "enumeration"
+    }
+
+    fn deduce(&self, clue: &[usize], line: &[CellState]) -> Option<Vec<(usize, CellState)>> {
+        let common =
+            placements(clue, line).fold(None, |common: Option<Vec<CellState>>, placed| {
+                Some(match common {
+                    None => placed,
+                    Some(common) => common
+                        .iter()
+                        .zip(&placed)
+                        .map(|(&a, &b)| if a == b { a } else { CellState::Unknown })
+                        .collect(),
+                })
+            })?;
+
+        unknown_only(
+            line,
+            common
+                .into_iter()
+                .enumerate()
+                .filter(|&(_, state)| state != CellState::Unknown)
+                .collect(),
+        )
+    }
+}
+
+/// Cells a rule determined in one line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deduction {
+    /// The `LineStrategy::name` of the rule that fired.
+    pub rule: &'static str,
+    pub line: Line,
+    /// Newly determined cells as `((row, column), state)`.
+    pub cells: Vec<((usize, usize), CellState)>,
+}
+
+/// The result of `Nonogram::solve_with_strategies`, along with every deduction in the order it
+/// was made.
+#[derive(Clone, Debug)]
+pub struct ExplainedSolve {
+    pub result: SolveResult,
+    pub deductions: Vec<Deduction>,
+}
+
+impl ExplainedSolve {
+    /// How many deductions each rule made.
+    pub fn rules_used(&self) -> BTreeMap<&'static str, usize> {
+        let mut used = BTreeMap::new();
+
+        for deduction in &self.deductions {
+            *used.entry(deduction.rule).or_insert(0) += 1;
+        }
+
+        used
+    }
+}
+
+impl Nonogram {
+    /// Solves the puzzle with the given rules, always going back to the first rule that can
+    /// still make progress, and records which rule determined which cells.
+    ///
+    /// Each pass in the returned stats is one sweep of a single rule over every row and then every
+    /// column. With `Enumeration` among the rules this reaches the same grid as `solve`.
+    pub fn solve_with_strategies(&self, strategies: &[Box<dyn LineStrategy>]) -> ExplainedSolve {
+        let options = SolveOptions::default();
+        let budget = Budget::start(&options);
+        let mut grid: Array2<CellState> = Array2::default((self.height(), self.width()));
+        let mut deductions = Vec::new();
+        let mut stats = SolveStats::default();
+        let lines: Vec<Line> = (0..self.height())
+            .map(Line::Row)
+            .chain((0..self.width()).map(Line::Column))
+            .collect();
+
+        let status = 'solve: loop {
+            // Rules are only trusted to be sound, so a finished grid is checked against the clues
+            // rather than taken as a solution.
+            if grid.iter().all(|&cell| cell != CellState::Unknown) {
+                let found =
+                    Nonogram::from_grid(grid.map(|&cell| (cell == CellState::Filled) as u8));
+
+                break if found.row_segments == self.row_segments
+                    && found.column_segments == self.column_segments
+                {
+                    SolveStatus::Solved
+                } else {
+                    SolveStatus::Contradiction
+                };
+            }
+
+            let mut progressed = false;
+
+            for strategy in strategies {
+                let mut cells = 0;
+
+                stats.passes += 1;
+
+                for &line in &lines {
+                    let (clue, current) = match line {
+                        Line::Row(i) => (&self.row_segments[i], grid.row(i).to_vec()),
+                        Line::Column(j) => (&self.column_segments[j], grid.column(j).to_vec()),
+                    };
+
+                    stats.lines_processed += 1;
+
+                    let found = match strategy
+                        .deduce(clue, &current)
+                        .and_then(|found| unknown_only(&current, found))
+                    {
+                        Some(found) => found,
+                        None => break 'solve SolveStatus::Contradiction,
+                    };
+
+                    if found.is_empty() {
+                        continue;
+                    }
+
+                    let found: Vec<((usize, usize), CellState)> = found
+                        .into_iter()
+                        .map(|(index, state)| match line {
+                            Line::Row(i) => ((i, index), state),
+                            Line::Column(j) => ((index, j), state),
+                        })
+                        .collect();
+
+                    for &(cell, state) in &found {
+                        grid[cell] = state;
+                    }
+
+                    cells += found.len();
+                    deductions.push(Deduction {
+                        rule: strategy.name(),
+                        line,
+                        cells: found,
+                    });
+                }
+
+                stats.cells_per_pass.push(cells);
+
+                if cells > 0 {
+                    progressed = true;
+                    break;
+                }
+            }
+
+            if !progressed {
+                break SolveStatus::Stalled;
+            }
+        };
+
+        stats.elapsed = budget.elapsed();
+
+        ExplainedSolve {
+            result: SolveResult {
+                status,
+                grid,
+                stats,
+            },
+            deductions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        default_strategies, leftmost, rightmost, EdgeForcing, Enumeration, GapJoining, Glue,
+        LineStrategy, Mercury, SimpleOverlap, SimpleSpaces,
+    };
+    use crate::CellState::{self, Empty, Filled, Unknown};
+
+    fn line(cells: &str) -> Vec<CellState> {
+        cells
+            .chars()
+            .map(|c| match c {
+                '#' => Filled,
+                'x' => Empty,
+                _ => Unknown,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn extreme_placements() {
+        assert_eq!(leftmost(&[2, 1], &line("......")), Some(vec![0, 3]));
+        assert_eq!(rightmost(&[2, 1], &line("......")), Some(vec![2, 5]));
+        assert_eq!(leftmost(&[2], &line("x.#...")), Some(vec![1]));
+        assert_eq!(leftmost(&[3], &line("..x..")), None);
+    }
+
+    #[test]
+    fn simple_overlap_fills_middle() {
+        assert_eq!(
+            SimpleOverlap.deduce(&[4], &line("......")),
+            Some(vec![(2, Filled), (3, Filled)])
+        );
+        assert_eq!(SimpleOverlap.deduce(&[2], &line("......")), Some(vec![]));
+    }
+
+    #[test]
+    fn simple_spaces_cross_out_unreachable_cells() {
+        assert_eq!(
+            SimpleSpaces.deduce(&[], &line("...")),
+            Some(vec![(0, Empty), (1, Empty), (2, Empty)])
+        );
+        assert_eq!(SimpleSpaces.deduce(&[1], &line("...")), Some(vec![]));
+    }
+
+    #[test]
+    fn edge_forcing_uses_known_cells() {
+        assert_eq!(
+            EdgeForcing.deduce(&[3], &line("#......")),
+            Some(vec![
+                (1, Filled),
+                (2, Filled),
+                (3, Empty),
+                (4, Empty),
+                (5, Empty),
+                (6, Empty)
+            ])
+        );
+        assert_eq!(
+            EdgeForcing.deduce(&[2], &line(".x....#")),
+            Some(vec![
+                (0, Empty),
+                (2, Empty),
+                (3, Empty),
+                (4, Empty),
+                (5, Filled)
+            ])
+        );
+    }
+
+    #[test]
+    fn glue_extends_blocks_from_either_edge() {
+        assert_eq!(
+            Glue.deduce(&[3], &line("x#....")),
+            Some(vec![(2, Filled), (3, Filled)])
+        );
+        assert_eq!(
+            Glue.deduce(&[1, 2], &line(".....#")),
+            Some(vec![(4, Filled)])
+        );
+        assert_eq!(Glue.deduce(&[2], &line("....#.")), Some(vec![]));
+    }
+
+    #[test]
+    fn mercury_empties_the_edge() {
+        assert_eq!(
+            Mercury.deduce(&[2], &line("..#...")),
+            Some(vec![(0, Empty)])
+        );
+        assert_eq!(
+            Mercury.deduce(&[1, 1], &line("#...#.")),
+            Some(vec![(5, Empty)])
+        );
+    }
+
+    #[test]
+    fn gap_joining_fills_and_splits_gaps() {
+        assert_eq!(
+            GapJoining.deduce(&[4], &line("#..#..")),
+            Some(vec![(1, Filled), (2, Filled)])
+        );
+        assert_eq!(
+            GapJoining.deduce(&[1, 2], &line("#.#...")),
+            Some(vec![(1, Empty)])
+        );
+    }
+
+    /// Every rule on every line of up to six cells against every clue that fits, compared with
+    /// what enumeration proves.
+    #[test]
+    fn rules_are_sound() {
+        let states = [Unknown, Empty, Filled];
+
+        for size in 1..=6 {
+            let clues: Vec<Vec<usize>> = (0..1usize << size)
+                .map(|bits| {
+                    let mut clue = Vec::new();
+                    let mut run = 0;
+
+                    for i in 0..=size {
+                        if i < size && bits >> i & 1 == 1 {
+                            run += 1;
+                        } else if run > 0 {
+                            clue.push(run);
+                            run = 0;
+                        }
+                    }
+
+                    clue
+                })
+                .collect();
+
+            for cells in 0..3usize.pow(size as u32) {
+                let line: Vec<CellState> = (0..size)
+                    .map(|i| states[cells / 3usize.pow(i as u32) % 3])
+                    .collect();
+
+                for clue in &clues {
+                    let proven = match Enumeration.deduce(clue, &line) {
+                        Some(proven) => proven,
+                        None => continue,
+                    };
+
+                    for strategy in default_strategies() {
+                        let found = strategy.deduce(clue, &line).unwrap();
+
+                        assert!(
+                            found.iter().all(|cell| proven.contains(cell)),
+                            "{} on {:?} with {:?}",
+                            strategy.name(),
+                            line,
+                            clue
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn enumeration_intersects_placements() {
+        assert_eq!(
+            Enumeration.deduce(&[1, 1], &line("..#..")),
+            Some(vec![(1, Empty), (3, Empty)])
+        );
+    }
+
+    #[test]
+    fn rules_report_contradictions() {
+        let impossible = line("#x#");
+
+        assert_eq!(EdgeForcing.deduce(&[3], &impossible), None);
+        assert_eq!(Enumeration.deduce(&[3], &impossible), None);
+        assert_eq!(SimpleOverlap.deduce(&[4], &line("...")), None);
+        assert_eq!(SimpleOverlap.deduce(&[3], &line(".x.")), None);
+    }
+}
@@ -0,0 +1,236 @@
+use super::prelude::*;
+use super::{CellState, Line, Nonogram, SolveSession, SolveStatus};
+use alloc::collections::BTreeSet;
+
+/// Pairs of undetermined cells `suggest_edits` tries at most. Their number grows with the square
+/// of the undetermined cells, so without a cap a large ambiguous picture would take hours.
+const MAX_PAIR_FLIPS: usize = 1000;
+
+/// A change to a puzzle's picture.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Edit {
+    /// Flips each listed cell, as `(row, column)`, between filled and empty.
+    Flip(Vec<(usize, usize)>),
+    /// Moves the `segment`th run of filled cells in a line by `offset` cells, towards the end of
+    /// the line when positive.
+    Shift {
+        line: Line,
+        segment: usize,
+        offset: isize,
+    },
+}
+
+/// An edit that makes the puzzle solvable, along with the puzzle it produces.
+#[derive(Clone, Debug)]
+pub struct Suggestion {
+    pub edit: Edit,
+    /// Every cell the edit changes, as `(row, column)`.
+    pub cells: Vec<(usize, usize)>,
+    pub puzzle: Nonogram,
+}
+
+/// Runs of filled cells in a line, as `(start, length)`.
+fn runs(line: &[u8]) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut index = 0;
+
+    while index < line.len() {
+        if line[index] == 1 {
+            let length = line[index..].iter().take_while(|&&cell| cell == 1).count();
+
+            runs.push((index, length));
+            index += length;
+        } else {
+            index += 1;
+        }
+    }
+
+    runs
+}
+
+/// Single-cell shifts of every run in `line` that keep a gap to the neighbouring runs, as the
+/// run index, the offset and the positions that flip.
+fn shifts(line: &[u8]) -> Vec<(usize, isize, [usize; 2])> {
+    let mut shifts = Vec::new();
+    let filled = |index: usize| line.get(index) == Some(&1);
+
+    for (segment, (start, length)) in runs(line).into_iter().enumerate() {
+        let end = start + length;
+
+        if start > 0 && (start < 2 || !filled(start - 2)) {
+            shifts.push((segment, -1, [start - 1, end - 1]));
+        }
+
+        if end < line.len() && !filled(end + 1) {
+            shifts.push((segment, 1, [start, end]));
+        }
+    }
+
+    shifts
+}
+
+impl Nonogram {
+    /// Proposes small changes to the picture that make the puzzle solvable, cheapest first.
+    ///
+    /// Tries flipping each cell the solver left undetermined and its neighbours, then shifting
+    /// runs by one cell in the lines that hold undetermined cells, and, if that isn't enough,
+    /// flipping pairs of undetermined cells, up to a thousand pairs. Suggestions are ranked by how
+    /// many cells they change. Returns nothing for a puzzle that is already solvable or has no
+    /// picture.
+    pub fn suggest_edits(&self, max_suggestions: usize) -> Vec<Suggestion> {
+        if self.is_clue_only() || max_suggestions == 0 {
+            return Vec::new();
+        }
+
+        let mut session = SolveSession::new(self.clone());
+
+        if session.status() == SolveStatus::Solved {
+            return Vec::new();
+        }
+
+        let unknown: Vec<(usize, usize)> = session
+            .result()
+            .grid
+            .indexed_iter()
+            .filter(|&(_, &cell)| cell == CellState::Unknown)
+            .map(|(index, _)| index)
+            .collect();
+        let mut candidates: Vec<(Edit, Vec<(usize, usize)>)> = Vec::new();
+        let mut flips = BTreeSet::new();
+
+        for &(i, j) in &unknown {
+            flips.insert((i, j));
+
+            if i > 0 {
+                flips.insert((i - 1, j));
+            }
+
+            if j > 0 {
+                flips.insert((i, j - 1));
+            }
+
+            if i + 1 < self.height() {
+                flips.insert((i + 1, j));
+            }
+
+            if j + 1 < self.width() {
+                flips.insert((i, j + 1));
+            }
+        }
+
+        candidates.extend(
+            flips
+                .into_iter()
+                .map(|cell| (Edit::Flip(vec![cell]), vec![cell])),
+        );
+
+        let rows: BTreeSet<usize> = unknown.iter().map(|&(i, _)| i).collect();
+        let columns: BTreeSet<usize> = unknown.iter().map(|&(_, j)| j).collect();
+
+        for &i in &rows {
+            let line = self.completed_grid.row(i).to_vec();
+
+            for (segment, offset, flipped) in shifts(&line) {
+                let edit = Edit::Shift {
+                    line: Line::Row(i),
+                    segment,
+                    offset,
+                };
+
+                candidates.push((edit, flipped.iter().map(|&j| (i, j)).collect()));
+            }
+        }
+
+        for &j in &columns {
+            let line = self.completed_grid.column(j).to_vec();
+
+            for (segment, offset, flipped) in shifts(&line) {
+                let edit = Edit::Shift {
+                    line: Line::Column(j),
+                    segment,
+                    offset,
+                };
+
+                candidates.push((edit, flipped.iter().map(|&i| (i, j)).collect()));
+            }
+        }
+
+        let mut suggestions = self.try_edits(&mut session, candidates);
+
+        if suggestions.len() < max_suggestions {
+            let pairs = unknown.iter().enumerate().flat_map(|(n, &first)| {
+                unknown[n + 1..]
+                    .iter()
+                    .map(move |&second| (Edit::Flip(vec![first, second]), vec![first, second]))
+            });
+
+            suggestions.extend(self.try_edits(&mut session, pairs.take(MAX_PAIR_FLIPS).collect()));
+        }
+
+        let mut seen = BTreeSet::new();
+
+        suggestions.sort_by_key(|suggestion| suggestion.cells.len());
+        suggestions.retain(|suggestion| {
+            let mut cells = suggestion.cells.clone();
+
+            cells.sort();
+            seen.insert(cells)
+        });
+        suggestions.truncate(max_suggestions);
+        suggestions
+    }
+
+    /// Keeps the edits after which the solver can finish, restoring the picture after each try.
+    /// The restored picture is only solved along with the next edit, which re-narrows the lines
+    /// both of them touched in one go.
+    fn try_edits(
+        &self,
+        session: &mut SolveSession,
+        candidates: Vec<(Edit, Vec<(usize, usize)>)>,
+    ) -> Vec<Suggestion> {
+        let mut suggestions = Vec::new();
+
+        for (edit, cells) in candidates {
+            let flipped: Vec<((usize, usize), bool)> = cells
+                .iter()
+                .map(|&cell| (cell, self.completed_grid[cell] == 0))
+                .collect();
+            let original: Vec<((usize, usize), bool)> = cells
+                .iter()
+                .map(|&cell| (cell, self.completed_grid[cell] == 1))
+                .collect();
+
+            if session.set_cells(&flipped) == SolveStatus::Solved {
+                suggestions.push(Suggestion {
+                    edit,
+                    cells,
+                    puzzle: session.puzzle().clone(),
+                });
+            }
+
+            session.edit(&original);
+        }
+
+        suggestions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{runs, shifts};
+
+    #[test]
+    fn finds_runs() {
+        assert_eq!(runs(&[1, 1, 0, 1, 0, 0, 1]), vec![(0, 2), (3, 1), (6, 1)]);
+        assert_eq!(runs(&[0, 0]), vec![]);
+    }
+
+    #[test]
+    fn shifts_keep_runs_apart() {
+        assert_eq!(
+            shifts(&[0, 1, 1, 0, 0, 1]),
+            vec![(0, -1, [0, 2]), (0, 1, [1, 3]), (1, -1, [4, 5])]
+        );
+        assert_eq!(shifts(&[1, 0, 1]), vec![]);
+    }
+}
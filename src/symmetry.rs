@@ -0,0 +1,337 @@
+use super::prelude::*;
+use super::Nonogram;
+use core::hash::{Hash, Hasher};
+use crc::crc64::checksum_ecma;
+use ndarray::{s, Array1, Array2, ArrayView2};
+
+/// One of the eight ways to rotate or mirror a grid onto itself.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+pub enum Transform {
+    Identity,
+    /// A quarter turn clockwise.
+    Rotate90,
+    Rotate180,
+    /// A quarter turn anticlockwise.
+    Rotate270,
+    /// Mirrors left and right.
+    FlipHorizontal,
+    /// Mirrors top and bottom.
+    FlipVertical,
+    /// Mirrors along the main diagonal, swapping rows and columns.
+    Transpose,
+    /// Mirrors along the other diagonal.
+    AntiTranspose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    /// Applies the transform to a grid, returning a copy in standard row-major layout.
+    pub fn apply<T: Clone>(self, grid: ArrayView2<T>) -> Array2<T> {
+        let view = match self {
+            Transform::Identity => grid,
+            Transform::Rotate90 => grid.reversed_axes().slice_move(s![.., ..;-1]),
+            Transform::Rotate180 => grid.slice_move(s![..;-1, ..;-1]),
+            Transform::Rotate270 => grid.reversed_axes().slice_move(s![..;-1, ..]),
+            Transform::FlipHorizontal => grid.slice_move(s![.., ..;-1]),
+            Transform::FlipVertical => grid.slice_move(s![..;-1, ..]),
+            Transform::Transpose => grid.reversed_axes(),
+            Transform::AntiTranspose => grid.reversed_axes().slice_move(s![..;-1, ..;-1]),
+        };
+
+        Array2::from_shape_fn(view.dim(), |index| view[index].clone())
+    }
+}
+
+/// Lays out a grid's dimensions followed by its cells, so grids of different shapes never share
+/// an encoding.
+fn encode(grid: &Array2<u8>) -> Vec<u8> {
+    let (height, width) = grid.dim();
+    let mut encoded = Vec::with_capacity(16 + grid.len());
+
+    encoded.extend_from_slice(&(height as u64).to_le_bytes());
+    encoded.extend_from_slice(&(width as u64).to_le_bytes());
+    encoded.extend(grid.iter());
+
+    encoded
+}
+
+/// Lays out the clues of a clue-only puzzle behind a marker no grid's height can take, followed
+/// by the number of rows and columns and then every clue with its length.
+fn encode_clues(rows: &Array1<Vec<usize>>, columns: &Array1<Vec<usize>>) -> Vec<u8> {
+    let mut encoded = Vec::new();
+    let mut push = |value: usize| encoded.extend_from_slice(&(value as u64).to_le_bytes());
+
+    push(usize::MAX);
+    push(rows.len());
+    push(columns.len());
+
+    for clue in rows.iter().chain(columns.iter()) {
+        push(clue.len());
+        clue.iter().for_each(|&length| push(length));
+    }
+
+    encoded
+}
+
+/// Mirrors left and right: every row clue reads backwards and the column clues swap ends.
+fn flip_clues_horizontal(
+    rows: Array1<Vec<usize>>,
+    columns: Array1<Vec<usize>>,
+) -> (Array1<Vec<usize>>, Array1<Vec<usize>>) {
+    let rows = rows
+        .into_iter()
+        .map(|clue| clue.iter().rev().cloned().collect())
+        .collect();
+    let columns = columns.iter().rev().cloned().collect();
+
+    (rows, columns)
+}
+
+/// Mirrors top and bottom, which is a horizontal flip with rows and columns swapped.
+fn flip_clues_vertical(
+    rows: Array1<Vec<usize>>,
+    columns: Array1<Vec<usize>>,
+) -> (Array1<Vec<usize>>, Array1<Vec<usize>>) {
+    let (columns, rows) = flip_clues_horizontal(columns, rows);
+
+    (rows, columns)
+}
+
+impl Nonogram {
+    /// Applies `transform` to the completed grid and rearranges the clues to match.
+    ///
+    /// Clue-only puzzles have just their clues rearranged.
+    pub fn transformed(&self, transform: Transform) -> Nonogram {
+        let rows = self.row_segments.clone();
+        let columns = self.column_segments.clone();
+
+        let (rows, columns) = match transform {
+            Transform::Identity => (rows, columns),
+            Transform::Rotate90 => flip_clues_horizontal(columns, rows),
+            Transform::Rotate180 => {
+                let (rows, columns) = flip_clues_horizontal(rows, columns);
+                flip_clues_vertical(rows, columns)
+            }
+            Transform::Rotate270 => flip_clues_vertical(columns, rows),
+            Transform::FlipHorizontal => flip_clues_horizontal(rows, columns),
+            Transform::FlipVertical => flip_clues_vertical(rows, columns),
+            Transform::Transpose => (columns, rows),
+            Transform::AntiTranspose => {
+                let (rows, columns) = flip_clues_horizontal(columns, rows);
+                flip_clues_vertical(rows, columns)
+            }
+        };
+
+        let completed_grid = if self.is_clue_only() {
+            self.completed_grid.clone()
+        } else {
+            transform.apply(self.completed_grid.view())
+        };
+
+        Nonogram {
+            row_segments: rows,
+            column_segments: columns,
+            completed_grid,
+        }
+    }
+
+    /// The puzzle turned a quarter turn clockwise.
+    pub fn rotate90(&self) -> Nonogram {
+        self.transformed(Transform::Rotate90)
+    }
+
+    pub fn rotate180(&self) -> Nonogram {
+        self.transformed(Transform::Rotate180)
+    }
+
+    /// The puzzle mirrored left to right.
+    pub fn flip_horizontal(&self) -> Nonogram {
+        self.transformed(Transform::FlipHorizontal)
+    }
+
+    /// The puzzle mirrored top to bottom.
+    pub fn flip_vertical(&self) -> Nonogram {
+        self.transformed(Transform::FlipVertical)
+    }
+
+    /// The puzzle with its rows and columns swapped.
+    pub fn transpose(&self) -> Nonogram {
+        self.transformed(Transform::Transpose)
+    }
+
+    /// The representative of this puzzle's picture among all of its rotations and reflections.
+    ///
+    /// Puzzles that are rotations or mirror images of each other share the same canonical form.
+    pub fn canonical_grid(&self) -> Array2<u8> {
+        Transform::ALL
+            .iter()
+            .map(|transform| transform.apply(self.completed_grid.view()))
+            .min_by_key(encode)
+            .unwrap()
+    }
+
+    /// A checksum of `canonical_grid` that also covers the puzzle's dimensions.
+    ///
+    /// Clue-only puzzles have no picture, so theirs covers the least of their clues under the
+    /// eight transforms instead.
+    ///
+    /// Unlike `generate_checksum`, this is the same for a picture and any of its rotations or
+    /// reflections, and it can be compared between puzzles of any size.
+    pub fn canonical_checksum(&self) -> u64 {
+        checksum_ecma(&self.canonical_encoding())
+    }
+
+    /// The encoded canonical grid, or for clue-only puzzles the least encoding of their
+    /// transformed clues.
+    fn canonical_encoding(&self) -> Vec<u8> {
+        if !self.is_clue_only() {
+            return encode(&self.canonical_grid());
+        }
+
+        Transform::ALL
+            .iter()
+            .map(|&transform| {
+                let transformed = self.transformed(transform);
+
+                encode_clues(&transformed.row_segments, &transformed.column_segments)
+            })
+            .min()
+            .unwrap()
+    }
+}
+
+/// Wraps a nonogram so that equality and hashing ignore rotations and reflections of its
+/// picture, or of its clues for clue-only puzzles.
+///
+/// Plain `Nonogram` equality treats a picture and its mirror image as different puzzles. Putting
+/// puzzles in a `HashSet<Canonical>` instead finds duplicates up to symmetry.
+#[derive(Debug, Clone)]
+pub struct Canonical {
+    nonogram: Nonogram,
+    encoded: Vec<u8>,
+    checksum: u64,
+}
+
+impl Canonical {
+    pub fn new(nonogram: Nonogram) -> Canonical {
+        let encoded = nonogram.canonical_encoding();
+        let checksum = checksum_ecma(&encoded);
+
+        Canonical {
+            nonogram,
+            encoded,
+            checksum,
+        }
+    }
+
+    pub fn nonogram(&self) -> &Nonogram {
+        &self.nonogram
+    }
+
+    pub fn into_inner(self) -> Nonogram {
+        self.nonogram
+    }
+
+    /// Same as `Nonogram::canonical_checksum`, computed once up front.
+    pub fn checksum(&self) -> u64 {
+        self.checksum
+    }
+}
+
+impl From<Nonogram> for Canonical {
+    fn from(nonogram: Nonogram) -> Self {
+        Canonical::new(nonogram)
+    }
+}
+
+impl Hash for Canonical {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.checksum.hash(state);
+    }
+}
+
+impl PartialEq for Canonical {
+    fn eq(&self, other: &Self) -> bool {
+        self.checksum == other.checksum && self.encoded == other.encoded
+    }
+}
+
+impl Eq for Canonical {}
+
+#[cfg(test)]
+mod tests {
+    use super::Transform;
+    use ndarray::arr2;
+
+    #[test]
+    fn rotate90_turns_clockwise() {
+        let grid = arr2(&[[1, 2, 3], [4, 5, 6]]);
+
+        assert_eq!(
+            Transform::Rotate90.apply(grid.view()),
+            arr2(&[[4, 1], [5, 2], [6, 3]])
+        );
+    }
+
+    #[test]
+    fn rotate270_turns_anticlockwise() {
+        let grid = arr2(&[[1, 2, 3], [4, 5, 6]]);
+
+        assert_eq!(
+            Transform::Rotate270.apply(grid.view()),
+            arr2(&[[3, 6], [2, 5], [1, 4]])
+        );
+    }
+
+    #[test]
+    fn flips_mirror_the_grid() {
+        let grid = arr2(&[[1, 2, 3], [4, 5, 6]]);
+
+        assert_eq!(
+            Transform::FlipHorizontal.apply(grid.view()),
+            arr2(&[[3, 2, 1], [6, 5, 4]])
+        );
+        assert_eq!(
+            Transform::FlipVertical.apply(grid.view()),
+            arr2(&[[4, 5, 6], [1, 2, 3]])
+        );
+    }
+
+    #[test]
+    fn transposes_mirror_diagonally() {
+        let grid = arr2(&[[1, 2, 3], [4, 5, 6]]);
+
+        assert_eq!(
+            Transform::Transpose.apply(grid.view()),
+            arr2(&[[1, 4], [2, 5], [3, 6]])
+        );
+        assert_eq!(
+            Transform::AntiTranspose.apply(grid.view()),
+            arr2(&[[6, 3], [5, 2], [4, 1]])
+        );
+    }
+
+    #[test]
+    fn all_transforms_are_distinct() {
+        let grid = arr2(&[[1, 2], [3, 4]]);
+        let mut transformed: Vec<_> = Transform::ALL
+            .iter()
+            .map(|transform| transform.apply(grid.view()).into_raw_vec())
+            .collect();
+
+        transformed.sort();
+        transformed.dedup();
+
+        assert_eq!(transformed.len(), 8);
+    }
+}
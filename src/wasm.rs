@@ -0,0 +1,329 @@
+//! JavaScript bindings for the `wasm` feature. See the README for building the module.
+//!
+//! Grids cross the boundary as flat row-major arrays with 1 for filled cells, 0 for empty cells
+//! and -1 for cells that aren't known yet, so a front-end can keep its board in an `Int8Array`.
+
+use super::prelude::*;
+use super::{CellState, GenerateOptions, Hint, Line, Nonogram, Progress, SolveStatus};
+use core::convert::TryFrom;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPES: &'static str = r#"
+/** A cell as 1 for filled, 0 for empty or -1 for not yet known. */
+export type Cell = 1 | 0 | -1;
+
+export interface SolveOutcome {
+    status: "solved" | "stalled" | "contradiction" | "budgetExceeded";
+    /** The grid row by row, with the cells the solver couldn't decide left at -1. */
+    cells: Cell[];
+}
+
+export type Hint =
+    | { kind: "mistake"; row: number; column: number }
+    | {
+          kind: "deduction";
+          row: number;
+          column: number;
+          state: Cell;
+          line: "row" | "column";
+          index: number;
+          rule: string;
+      }
+    | { kind: "reveal"; row: number; column: number; state: Cell };
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "SolveOutcome")]
+    pub type JsSolveOutcome;
+
+    #[wasm_bindgen(typescript_type = "Hint | undefined")]
+    pub type JsHint;
+
+    #[wasm_bindgen(typescript_type = "number[][]")]
+    pub type JsClues;
+
+    #[wasm_bindgen(typescript_type = "number | bigint")]
+    pub type JsSeed;
+}
+
+#[derive(Serialize)]
+struct SolveOutcome {
+    status: &'static str,
+    cells: Vec<i8>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum HintObject {
+    Mistake {
+        row: usize,
+        column: usize,
+    },
+    Deduction {
+        row: usize,
+        column: usize,
+        state: i8,
+        line: &'static str,
+        index: usize,
+        rule: &'static str,
+    },
+    Reveal {
+        row: usize,
+        column: usize,
+        state: i8,
+    },
+}
+
+impl From<Hint> for HintObject {
+    fn from(hint: Hint) -> HintObject {
+        match hint {
+            Hint::Mistake { row, column } => HintObject::Mistake { row, column },
+            Hint::Deduction {
+                row,
+                column,
+                state,
+                line,
+                rule,
+            } => {
+                let (line, index) = match line {
+                    Line::Row(index) => ("row", index),
+                    Line::Column(index) => ("column", index),
+                };
+
+                HintObject::Deduction {
+                    row,
+                    column,
+                    state: cell_to_js(state),
+                    line,
+                    index,
+                    rule,
+                }
+            }
+            Hint::Reveal { row, column, state } => HintObject::Reveal {
+                row,
+                column,
+                state: cell_to_js(state),
+            },
+        }
+    }
+}
+
+fn cell_to_js(state: CellState) -> i8 {
+    match state {
+        CellState::Filled => 1,
+        CellState::Empty => 0,
+        CellState::Unknown => -1,
+    }
+}
+
+fn cell_from_js(value: i8) -> Result<CellState, JsError> {
+    match value {
+        1 => Ok(CellState::Filled),
+        0 => Ok(CellState::Empty),
+        -1 => Ok(CellState::Unknown),
+        _ => Err(JsError::new(&format!(
+            "{} is not a cell, expected 1, 0 or -1",
+            value
+        ))),
+    }
+}
+
+/// Reads a seed given as a whole `number` up to `Number.MAX_SAFE_INTEGER`, or as a `BigInt` for
+/// the rest of the 64-bit seeds the library uses.
+fn seed_from_js(seed: JsValue) -> Result<u64, JsError> {
+    const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+    let seed = match seed.as_f64() {
+        Some(number) if (0.0..=MAX_SAFE_INTEGER).contains(&number) && number.fract() == 0.0 => {
+            Some(number as u64)
+        }
+        Some(_) => None,
+        None => u64::try_from(seed).ok(),
+    };
+
+    seed.ok_or_else(|| {
+        JsError::new(
+            "seed must be a whole number from 0 to 2^53 - 1 or a BigInt from 0 to 2^64 - 1",
+        )
+    })
+}
+
+/// Plain data always serializes, so only the TypeScript type is left to attach.
+fn to_js<T: Serialize, U: JsCast>(value: &T) -> U {
+    serde_wasm_bindgen::to_value(value)
+        .expect_throw("plain data serializes")
+        .unchecked_into()
+}
+
+/// A puzzle for JavaScript, wrapping a `Nonogram`.
+#[wasm_bindgen]
+pub struct Puzzle {
+    inner: Nonogram,
+}
+
+#[wasm_bindgen]
+impl Puzzle {
+    /// Generates a random puzzle. Without a seed one is drawn from `Math.random`.
+    ///
+    /// The seed may be a `number` or, to reach every seed the Rust library accepts, a `BigInt`.
+    ///
+    /// `density` defaults to 0.5. With `unique` set only puzzles the solver can finish are
+    /// accepted, and an error is thrown when none turns up.
+    pub fn generate(
+        width: usize,
+        height: usize,
+        seed: Option<JsSeed>,
+        density: Option<f64>,
+        unique: Option<bool>,
+    ) -> Result<Puzzle, JsError> {
+        let options = GenerateOptions {
+            seed: seed.map(|seed| seed_from_js(seed.into())).transpose()?,
+            density: density.unwrap_or(0.5),
+            require_unique: unique.unwrap_or(false),
+            ..GenerateOptions::new(width, height)
+        };
+
+        if !(0.0..=1.0).contains(&options.density) {
+            return Err(JsError::new("density must be between 0 and 1"));
+        }
+
+        Nonogram::generate_with(&options)
+            .map(Puzzle::from)
+            .ok_or_else(|| {
+                JsError::new(&format!(
+                    "no uniquely solvable puzzle found in {} attempts",
+                    options.max_attempts
+                ))
+            })
+    }
+
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<Puzzle, JsError> {
+        Ok(Nonogram::from_json(json)?.into())
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsError> {
+        Ok(self.inner.as_json()?)
+    }
+
+    /// Reads a share code such as `4x2:gQ`, see `Nonogram::from_code`.
+    #[wasm_bindgen(js_name = fromCode)]
+    pub fn from_code(code: &str) -> Result<Puzzle, JsError> {
+        Ok(Nonogram::from_code(code)?.into())
+    }
+
+    /// The puzzle's share code, or `undefined` for a puzzle made from clues alone.
+    #[wasm_bindgen(js_name = toCode)]
+    pub fn to_code(&self) -> Option<String> {
+        self.inner.as_code()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> usize {
+        self.inner.width()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> usize {
+        self.inner.height()
+    }
+
+    #[wasm_bindgen(getter, js_name = rowClues)]
+    pub fn row_clues(&self) -> JsClues {
+        to_js(&self.inner.row_segments.to_vec())
+    }
+
+    #[wasm_bindgen(getter, js_name = columnClues)]
+    pub fn column_clues(&self) -> JsClues {
+        to_js(&self.inner.column_segments.to_vec())
+    }
+
+    /// The completed grid, or `undefined` for a puzzle made from clues alone.
+    pub fn solution(&self) -> Option<Vec<i8>> {
+        if self.inner.is_clue_only() {
+            return None;
+        }
+
+        Some(
+            self.inner
+                .completed_grid
+                .iter()
+                .map(|&cell| cell as i8)
+                .collect(),
+        )
+    }
+
+    /// Runs the line solver on the clues.
+    pub fn solve(&self) -> JsSolveOutcome {
+        let result = self.inner.solve();
+        let status = match result.status {
+            SolveStatus::Solved => "solved",
+            SolveStatus::Stalled => "stalled",
+            SolveStatus::Contradiction => "contradiction",
+            SolveStatus::BudgetExceeded => "budgetExceeded",
+        };
+
+        to_js(&SolveOutcome {
+            status,
+            cells: result.grid.iter().map(|&cell| cell_to_js(cell)).collect(),
+        })
+    }
+
+    /// Throws if the clues don't fit their lines or don't match the grid.
+    pub fn validate(&self) -> Result<(), JsError> {
+        Ok(self.inner.validate()?)
+    }
+
+    /// Suggests the player's next move on the board `cells`, see `Progress::hint`.
+    ///
+    /// Returns `undefined` once the board is complete.
+    pub fn hint(&self, cells: &[i8]) -> Result<JsHint, JsError> {
+        let progress = self.progress(cells)?;
+
+        Ok(match progress.hint() {
+            Some(hint) => to_js(&HintObject::from(hint)),
+            None => JsValue::UNDEFINED.unchecked_into(),
+        })
+    }
+
+    /// Whether the board `cells` satisfies every clue.
+    #[wasm_bindgen(js_name = isComplete)]
+    pub fn is_complete(&self, cells: &[i8]) -> Result<bool, JsError> {
+        Ok(self.progress(cells)?.is_complete())
+    }
+}
+
+impl Puzzle {
+    fn progress(&self, cells: &[i8]) -> Result<Progress, JsError> {
+        let (height, width) = (self.inner.height(), self.inner.width());
+
+        if cells.len() != width * height {
+            return Err(JsError::new(&format!(
+                "expected {} cells for a {}x{} puzzle, got {}",
+                width * height,
+                width,
+                height,
+                cells.len()
+            )));
+        }
+
+        let mut progress = Progress::new(self.inner.clone());
+
+        for (index, &value) in cells.iter().enumerate() {
+            progress.set(index / width, index % width, cell_from_js(value)?);
+        }
+
+        Ok(progress)
+    }
+}
+
+impl From<Nonogram> for Puzzle {
+    fn from(inner: Nonogram) -> Puzzle {
+        Puzzle { inner }
+    }
+}
@@ -0,0 +1,129 @@
+use nonogram::{arr1, arr2, Array1, Array2, Edit, Nonogram};
+
+#[test]
+fn unique_puzzle_has_no_ambiguity() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 1, 1], [0, 1, 0], [1, 1, 1]]));
+    let report = puzzle.ambiguity(4);
+
+    assert!(report.unknown.is_empty());
+    assert!(report.differing.is_empty());
+    assert!(report.is_unique());
+    assert_eq!(report.solutions, vec![puzzle.completed_grid]);
+}
+
+#[test]
+fn swappable_corners_are_reported() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 0, 0], [0, 1, 0], [0, 0, 0]]));
+    let report = puzzle.ambiguity(8);
+
+    assert!(report.complete);
+    assert_eq!(report.solutions.len(), 2);
+    assert_eq!(report.unknown, vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+    assert_eq!(report.differing, report.unknown);
+    assert_eq!(report.regions, vec![vec![(0, 0), (0, 1), (1, 0), (1, 1)]]);
+}
+
+#[test]
+fn separate_regions_are_grouped() {
+    let puzzle = Nonogram::from_grid(arr2(&[
+        [1, 0, 0, 0, 0],
+        [0, 1, 0, 0, 0],
+        [0, 0, 0, 0, 0],
+        [0, 0, 0, 1, 0],
+        [0, 0, 0, 0, 1],
+    ]));
+    let report = puzzle.ambiguity(32);
+
+    // Any of the four rows may take any of the four columns, and the empty middle row and
+    // column split the varying cells into four corners.
+    assert!(report.complete);
+    assert_eq!(report.solutions.len(), 24);
+    assert_eq!(report.regions.len(), 4);
+    assert!(report.regions.iter().all(|region| region.len() == 4));
+    assert!(!report.is_unique());
+}
+
+#[test]
+fn limit_stops_the_search() {
+    let clues = Array1::from(vec![vec![1]; 4]);
+    let puzzle = Nonogram::from_clues(clues.clone(), clues);
+    let report = puzzle.ambiguity(3);
+
+    assert_eq!(report.solutions.len(), 3);
+    assert!(!report.complete);
+}
+
+#[test]
+fn contradictory_clues_have_no_solutions() {
+    let puzzle = Nonogram::from_clues(arr1(&[vec![2], vec![]]), arr1(&[vec![2], vec![]]));
+    let report = puzzle.ambiguity(2);
+
+    assert!(report.solutions.is_empty());
+    assert!(report.complete);
+    assert!(!report.is_unique());
+}
+
+#[test]
+fn suggests_single_flips_first() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 0, 0], [0, 1, 0], [0, 0, 0]]));
+    let suggestions = puzzle.suggest_edits(5);
+
+    assert!(!suggestions.is_empty());
+    assert_eq!(suggestions[0].cells.len(), 1);
+    assert!(suggestions
+        .iter()
+        .all(|suggestion| suggestion.puzzle.solvable()));
+    assert!(suggestions
+        .windows(2)
+        .all(|pair| pair[0].cells.len() <= pair[1].cells.len()));
+
+    for suggestion in &suggestions {
+        let changed: Vec<(usize, usize)> = puzzle
+            .completed_grid
+            .indexed_iter()
+            .filter(|&(index, &cell)| suggestion.puzzle.completed_grid[index] != cell)
+            .map(|(index, _)| index)
+            .collect();
+        let mut cells = suggestion.cells.clone();
+
+        cells.sort();
+        assert_eq!(changed, cells);
+    }
+}
+
+#[test]
+fn suggests_shifting_segments() {
+    let puzzle = Nonogram::from_grid(arr2(&[
+        [1, 1, 0, 0],
+        [0, 0, 1, 1],
+        [1, 1, 0, 0],
+        [0, 0, 1, 1],
+    ]));
+    let suggestions = puzzle.suggest_edits(50);
+
+    assert!(suggestions
+        .iter()
+        .any(|suggestion| matches!(suggestion.edit, Edit::Shift { .. })));
+    assert!(suggestions
+        .iter()
+        .all(|suggestion| suggestion.puzzle.solvable()));
+}
+
+#[test]
+fn gives_up_on_pictures_no_small_edit_fixes() {
+    // Sixteen separate diagonals, each of which the clues can't tell from the other diagonal of
+    // its 2x2 block, leave more pairs of undetermined cells than are tried.
+    let puzzle = Nonogram::from_grid(Array2::from_shape_fn((12, 12), |(i, j)| {
+        (i % 3 < 2 && i % 3 == j % 3) as u8
+    }));
+
+    assert_eq!(puzzle.ambiguity(1).unknown.len(), 64);
+    assert!(puzzle.suggest_edits(3).is_empty());
+}
+
+#[test]
+fn solvable_puzzles_need_no_edits() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 1, 1], [0, 1, 0], [1, 1, 1]]));
+
+    assert!(puzzle.suggest_edits(3).is_empty());
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "std")]
+
+use nonogram::{CancelToken, GenerateOptions, Nonogram, SolveOptions, SolveStatus};
+
+use std::time::Duration;
+
+fn puzzle() -> Nonogram {
+    let options = GenerateOptions {
+        seed: Some(5),
+        require_unique: true,
+        ..GenerateOptions::new(10, 10)
+    };
+
+    Nonogram::generate_with(&options).unwrap()
+}
+
+#[test]
+fn default_options_solve_fully() {
+    let puzzle = puzzle();
+    let result = puzzle.solve_with_options(&SolveOptions::default());
+
+    assert_eq!(result.status, SolveStatus::Solved);
+    assert_eq!(result.grid, puzzle.solve().grid);
+}
+
+#[test]
+fn pass_limit_stops_early() {
+    let options = SolveOptions {
+        max_passes: Some(0),
+        ..SolveOptions::default()
+    };
+
+    assert_eq!(
+        puzzle().solve_with_options(&options).status,
+        SolveStatus::BudgetExceeded
+    );
+}
+
+#[test]
+fn line_candidate_limit_stops_enumeration() {
+    let options = SolveOptions {
+        max_line_candidates: Some(1),
+        ..SolveOptions::default()
+    };
+    let nonogram = Nonogram::generate_with(&GenerateOptions {
+        seed: Some(30),
+        ..GenerateOptions::new(30, 30)
+    })
+    .unwrap();
+    let result = nonogram.solve_with_options(&options);
+
+    assert_eq!(result.status, SolveStatus::BudgetExceeded);
+}
+
+#[test]
+fn zero_time_limit_gives_up() {
+    let options = SolveOptions {
+        max_time: Some(Duration::from_secs(0)),
+        ..SolveOptions::default()
+    };
+
+    assert_eq!(
+        puzzle().solve_with_options(&options).status,
+        SolveStatus::BudgetExceeded
+    );
+}
+
+#[test]
+fn cancelled_token_stops_solver() {
+    let token = CancelToken::new();
+    let options = SolveOptions {
+        cancel: Some(token.clone()),
+        ..SolveOptions::default()
+    };
+
+    token.cancel();
+
+    assert!(token.is_cancelled());
+    assert_eq!(
+        puzzle().solve_with_options(&options).status,
+        SolveStatus::BudgetExceeded
+    );
+}
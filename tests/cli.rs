@@ -0,0 +1,129 @@
+#![cfg(feature = "cli")]
+
+use nonogram::Nonogram;
+use std::process::Command;
+
+const PUZZLE: &str = "{\"checksum\":\"3087051523477295210\",\"height\":5,\"width\":5,\"row_segments\":[[],[1,1],[],[1,1],[]],\"column_segments\":[[],[1,1],[],[1,1],[]],\"completed_grid\":[[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0]]}";
+
+fn nonogram() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_nonogram"))
+}
+
+fn puzzle_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("nonogram-cli-{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn generate_is_reproducible_with_seed() {
+    let run = || {
+        nonogram()
+            .args(["generate", "--width", "8", "--height", "6", "--seed", "11"])
+            .output()
+            .unwrap()
+    };
+
+    let first = run();
+    let second = run();
+
+    assert!(first.status.success());
+    assert_eq!(first.stdout, second.stdout);
+
+    let puzzle = Nonogram::from_json(std::str::from_utf8(&first.stdout).unwrap()).unwrap();
+    assert_eq!((puzzle.height(), puzzle.width()), (6, 8));
+}
+
+#[test]
+fn solve_prints_solution() {
+    let path = puzzle_file("solve.json", PUZZLE.as_bytes());
+    let output = nonogram().arg("solve").arg(&path).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("1 1  . # . # ."));
+    assert!(stdout.ends_with("solved\n"));
+}
+
+#[test]
+fn solve_rejects_invalid_timeouts() {
+    let path = puzzle_file("timeout.json", PUZZLE.as_bytes());
+
+    for timeout in [
+        "--timeout=-1",
+        "--timeout=NaN",
+        "--timeout=inf",
+        "--timeout=soon",
+    ] {
+        let output = nonogram()
+            .arg("solve")
+            .arg(&path)
+            .arg(timeout)
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+
+        assert_eq!(output.status.code(), Some(2), "{}", timeout);
+        assert!(stderr.contains("invalid value"), "{}", stderr);
+        assert!(!stderr.contains("panicked"), "{}", stderr);
+    }
+
+    let output = nonogram()
+        .arg("solve")
+        .arg(&path)
+        .arg("--timeout=2.5")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+}
+
+#[test]
+fn convert_round_trips_through_msgpack() {
+    let input = puzzle_file("convert.json", PUZZLE.as_bytes());
+    let output = puzzle_file("convert.msgpack", b"");
+
+    assert!(nonogram()
+        .arg("convert")
+        .arg(&input)
+        .arg(&output)
+        .status()
+        .unwrap()
+        .success());
+
+    let converted = Nonogram::from_msgpack(&std::fs::read(&output).unwrap()).unwrap();
+    assert_eq!(converted.as_json().unwrap(), PUZZLE);
+}
+
+#[test]
+fn validate_rejects_mismatched_clues() {
+    let path = puzzle_file(
+        "validate.json",
+        PUZZLE
+            .replace(
+                "[[],[1,1],[],[1,1],[]],\"column",
+                "[[],[2],[],[1,1],[]],\"column",
+            )
+            .as_bytes(),
+    );
+    let output = nonogram().arg("validate").arg(&path).output().unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .starts_with("invalid: clue for row 1"));
+}
+
+#[test]
+fn validate_solvable_points_at_ambiguous_cells() {
+    let puzzle = Nonogram::from_grid(nonogram::arr2(&[[1, 0, 0], [0, 1, 0], [0, 0, 0]]));
+    let path = puzzle_file("ambiguous.json", puzzle.as_json().unwrap().as_bytes());
+    let output = nonogram()
+        .args(["validate", "--solvable"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout.contains("4 cells can change together starting at row 0, column 0"));
+}
@@ -0,0 +1,166 @@
+#[cfg(feature = "std")]
+use nonogram::Array2;
+use nonogram::{arr1, arr2, Cnf, Nonogram};
+#[cfg(feature = "std")]
+use std::{env, fs, process::Command};
+
+/// Counts up to `limit` models of a formula by plain DPLL, collecting the cell values of each.
+fn models(cnf: &Cnf, cells: usize, limit: usize) -> Vec<Vec<bool>> {
+    fn search(
+        clauses: &[Vec<i64>],
+        mut assignment: Vec<Option<bool>>,
+        cells: usize,
+        limit: usize,
+        found: &mut Vec<Vec<bool>>,
+    ) {
+        loop {
+            let mut unit = None;
+
+            for clause in clauses {
+                let value = |literal: i64| {
+                    assignment[literal.unsigned_abs() as usize - 1].map(|v| v == (literal > 0))
+                };
+
+                if clause.iter().any(|&literal| value(literal) == Some(true)) {
+                    continue;
+                }
+
+                let open: Vec<i64> = clause
+                    .iter()
+                    .copied()
+                    .filter(|&literal| value(literal).is_none())
+                    .collect();
+
+                match open.len() {
+                    0 => return,
+                    1 => unit = Some(open[0]),
+                    _ => (),
+                }
+            }
+
+            match unit {
+                Some(literal) => {
+                    assignment[literal.unsigned_abs() as usize - 1] = Some(literal > 0);
+                }
+                None => break,
+            }
+        }
+
+        match assignment.iter().position(Option::is_none) {
+            Some(variable) => {
+                for &value in &[true, false] {
+                    if found.len() < limit {
+                        let mut assignment = assignment.clone();
+
+                        assignment[variable] = Some(value);
+                        search(clauses, assignment, cells, limit, found);
+                    }
+                }
+            }
+            None => found.push(assignment[..cells].iter().map(|v| v.unwrap()).collect()),
+        }
+    }
+
+    let mut found = Vec::new();
+
+    search(
+        &cnf.clauses,
+        vec![None; cnf.variables],
+        cells,
+        limit,
+        &mut found,
+    );
+    found
+}
+
+#[test]
+fn unique_puzzle_has_one_model() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 1, 1], [0, 1, 0], [1, 1, 1]]));
+    let found = models(&puzzle.to_cnf(), 9, 10);
+    let expected: Vec<bool> = puzzle
+        .completed_grid
+        .iter()
+        .map(|&cell| cell == 1)
+        .collect();
+
+    assert_eq!(found, vec![expected]);
+}
+
+#[test]
+fn ambiguous_puzzle_has_every_solution() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 0], [0, 1]]));
+    let mut cnf = puzzle.to_cnf();
+
+    assert_eq!(models(&cnf, 4, 10).len(), 2);
+
+    cnf.exclude(&puzzle.completed_grid);
+    assert_eq!(models(&cnf, 4, 10), vec![vec![false, true, true, false]]);
+}
+
+#[test]
+fn clue_too_long_is_unsatisfiable() {
+    let puzzle = Nonogram::from_clues(arr1(&[vec![1, 1], vec![]]), arr1(&[vec![1], vec![]]));
+
+    assert!(models(&puzzle.to_cnf(), 4, 10).is_empty());
+}
+
+#[test]
+fn writes_dimacs() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1]]));
+
+    assert_eq!(
+        puzzle.to_dimacs(),
+        "p cnf 3 6\n2 0\n-2 1 0\n-1 2 0\n3 0\n-3 1 0\n-1 3 0\n"
+    );
+}
+
+#[test]
+fn reads_models() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 0], [0, 1]]));
+
+    assert_eq!(
+        puzzle
+            .grid_from_model("c comment\ns SATISFIABLE\nv 1 -2 -3\nv 4 -5 6 0\n")
+            .unwrap(),
+        Some(arr2(&[[1, 0], [0, 1]]))
+    );
+    assert_eq!(
+        puzzle.grid_from_model("SAT\n-1 2 3 -4 0\n").unwrap(),
+        Some(arr2(&[[0, 1], [1, 0]]))
+    );
+    assert_eq!(puzzle.grid_from_model("s UNSATISFIABLE\n").unwrap(), None);
+    assert!(puzzle.grid_from_model("v 1 -2 0\n").is_err());
+    assert!(puzzle.grid_from_model("v 1 x 0\n").is_err());
+}
+
+/// Cross-checks against a real SAT solver when `NONOGRAM_SAT_SOLVER` names one that takes a
+/// DIMACS file and prints its model.
+#[test]
+#[cfg(feature = "std")]
+fn external_solver_agrees() {
+    let solver = match env::var("NONOGRAM_SAT_SOLVER") {
+        Ok(solver) => solver,
+        Err(_) => return,
+    };
+    let puzzle = Nonogram::generate(12, 12);
+    let path = env::temp_dir().join(format!("nonogram-{}.cnf", std::process::id()));
+    let mut cnf = puzzle.to_cnf();
+    let solve = |cnf: &Cnf| -> Option<Array2<u8>> {
+        fs::write(&path, cnf.to_dimacs()).unwrap();
+
+        let output = Command::new(&solver).arg(&path).output().unwrap();
+
+        puzzle
+            .grid_from_model(&String::from_utf8_lossy(&output.stdout))
+            .unwrap()
+    };
+    let first = solve(&cnf).unwrap();
+    let solved = Nonogram::from_grid(first.clone());
+
+    assert_eq!(solved.row_segments, puzzle.row_segments);
+    assert_eq!(solved.column_segments, puzzle.column_segments);
+
+    cnf.exclude(&first);
+    assert_eq!(solve(&cnf).is_none(), puzzle.ambiguity(2).is_unique());
+    fs::remove_file(&path).unwrap();
+}
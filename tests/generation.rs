@@ -1,6 +1,11 @@
-use nonogram::Nonogram;
+#![cfg(feature = "std")]
 
-use ndarray::{arr1, arr2};
+use nonogram::{
+    CellState, ColorNonogram, Difficulty, GenerateOptions, Nonogram, Pattern, SolveOptions,
+    SolveStatus, Symmetry,
+};
+
+use ndarray::{arr1, arr2, s};
 
 #[test]
 fn random_generation_works() {
@@ -14,6 +19,7 @@ fn random_generation_works() {
 }
 
 #[test]
+#[allow(clippy::overly_complex_bool_expr)]
 fn mismatched_dimensions_work() {
     let puzzle1 = Nonogram::generate(5, 10).solvable();
     let puzzle2 = Nonogram::generate(15, 5).solvable();
@@ -57,3 +63,301 @@ fn detects_solvable() {
 
     assert!(puzzle.solvable())
 }
+
+#[test]
+fn seeded_generation_is_reproducible() {
+    let mut options = GenerateOptions::new(12, 8);
+    options.seed = Some(42);
+
+    let puzzle1 = Nonogram::generate_with(&options).unwrap();
+    let puzzle2 = Nonogram::generate_with(&options).unwrap();
+
+    assert_eq!(puzzle1.completed_grid, puzzle2.completed_grid);
+    assert_eq!(puzzle1.completed_grid.dim(), (8, 12));
+    assert!(puzzle1.validate().is_ok());
+}
+
+#[test]
+fn generation_respects_density() {
+    let mut options = GenerateOptions::new(10, 10);
+    options.density = 0.0;
+
+    let puzzle = Nonogram::generate_with(&options).unwrap();
+
+    assert!(puzzle.completed_grid.iter().all(|&cell| cell == 0));
+}
+
+#[test]
+fn out_of_range_density_is_clamped() {
+    let mut options = GenerateOptions::new(6, 4);
+
+    for &(density, cell) in &[(1.5, 1), (-0.5, 0), (f64::NAN, 0)] {
+        options.density = density;
+
+        let puzzle = Nonogram::generate_with(&options).unwrap();
+        let colored = ColorNonogram::generate_with(&options, vec![[255; 3], [0; 3]]).unwrap();
+
+        assert!(
+            puzzle.completed_grid.iter().all(|&c| c == cell),
+            "{}",
+            density
+        );
+        assert!(
+            colored.completed_grid.iter().all(|&c| c == cell),
+            "{}",
+            density
+        );
+    }
+
+    options.density = f64::NAN;
+    options.pattern = Pattern::Noise { scale: 2 };
+
+    let puzzle = Nonogram::generate_with(&options).unwrap();
+
+    assert!(puzzle.completed_grid.iter().all(|&cell| cell == 0));
+}
+
+#[test]
+fn generation_requiring_uniqueness_is_solvable() {
+    let mut options = GenerateOptions::new(8, 8);
+    options.seed = Some(7);
+    options.require_unique = true;
+
+    assert!(Nonogram::generate_with(&options).unwrap().solvable());
+}
+
+#[test]
+fn solve_reports_stalled_cells() {
+    let puzzle = Nonogram {
+        row_segments: arr1(&[vec![], vec![1], vec![], vec![1], vec![]]),
+        column_segments: arr1(&[vec![], vec![1], vec![], vec![1], vec![]]),
+        completed_grid: arr2(&[
+            [0, 0, 0, 0, 0],
+            [0, 1, 0, 0, 0],
+            [0, 0, 0, 0, 0],
+            [0, 0, 0, 1, 0],
+            [0, 0, 0, 0, 0],
+        ]),
+    };
+
+    let result = puzzle.solve();
+
+    assert_eq!(result.status, SolveStatus::Stalled);
+    assert_eq!(result.grid[[0, 0]], CellState::Empty);
+    assert_eq!(result.grid[[1, 1]], CellState::Unknown);
+    assert_eq!(result.grid[[3, 3]], CellState::Unknown);
+}
+
+#[test]
+fn solve_reports_contradiction() {
+    let puzzle = Nonogram {
+        row_segments: arr1(&[vec![3], vec![]]),
+        column_segments: arr1(&[vec![1], vec![], vec![]]),
+        completed_grid: arr2(&[[1, 1, 1], [0, 0, 0]]),
+    };
+
+    assert_eq!(puzzle.solve().status, SolveStatus::Contradiction);
+}
+
+#[test]
+fn solve_reports_clue_too_long() {
+    let puzzle = Nonogram {
+        row_segments: arr1(&[vec![4], vec![]]),
+        column_segments: arr1(&[vec![1], vec![1], vec![1]]),
+        completed_grid: arr2(&[[1, 1, 1], [0, 0, 0]]),
+    };
+
+    assert_eq!(puzzle.solve().status, SolveStatus::Contradiction);
+}
+
+#[test]
+fn solve_reports_stats() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 1, 1], [0, 1, 0], [1, 1, 1]]));
+    let result = puzzle.solve();
+    let stats = &result.stats;
+
+    assert_eq!(result.status, SolveStatus::Solved);
+    assert_eq!(stats.passes, stats.cells_per_pass.len());
+    assert_eq!(stats.lines_processed, stats.passes * 6);
+    assert_eq!(stats.cells_resolved(), 9);
+    assert_eq!(stats.row_candidates.len(), 3);
+    assert_eq!(stats.column_candidates.len(), 3);
+    assert_eq!(stats.row_candidates[1].initial, 3);
+    assert!(stats
+        .row_candidates
+        .iter()
+        .chain(&stats.column_candidates)
+        .all(|line| line.remaining <= line.initial && line.remaining > 0));
+    assert_eq!((stats.probes, stats.backtracks), (0, 0));
+}
+
+#[test]
+fn stats_of_stalled_puzzle_keep_candidates() {
+    let puzzle = Nonogram::from_clues(arr1(&[vec![1], vec![1]]), arr1(&[vec![1], vec![1]]));
+    let stats = puzzle.solve().stats;
+
+    assert_eq!(stats.passes, 1);
+    assert_eq!(stats.cells_per_pass, vec![0]);
+    assert_eq!(stats.initial_candidates(), 8);
+}
+
+#[test]
+fn colour_solver_agrees_on_two_colour_puzzles() {
+    for seed in 0..20 {
+        let mut options = GenerateOptions::new(7, 6);
+        options.seed = Some(seed);
+
+        let puzzle = Nonogram::generate_with(&options).unwrap();
+        let coloured =
+            ColorNonogram::from_grid(vec![[255; 3], [0; 3]], puzzle.completed_grid.clone())
+                .unwrap();
+
+        assert_eq!(puzzle.solvable(), coloured.solvable());
+    }
+}
+
+#[test]
+fn rates_difficulty() {
+    let full = Nonogram::from_grid(arr2(&[[1, 1], [1, 1]]));
+    let diagonal = Nonogram::from_grid(arr2(&[[1, 0], [0, 1]]));
+
+    assert_eq!(full.difficulty(), Difficulty::Trivial);
+    assert_eq!(diagonal.difficulty(), Difficulty::Unsolvable);
+}
+
+#[test]
+fn generates_within_difficulty_band() {
+    for &difficulty in &[Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+        let options = GenerateOptions {
+            seed: Some(11),
+            difficulty: Some(difficulty..=difficulty),
+            ..GenerateOptions::new(8, 8)
+        };
+        let puzzle = Nonogram::generate_with(&options).unwrap();
+
+        assert_eq!(puzzle.difficulty(), difficulty);
+        assert_eq!(Nonogram::generate_with(&options), Some(puzzle));
+    }
+}
+
+#[test]
+fn rating_gives_up_when_out_of_budget() {
+    let options = GenerateOptions {
+        seed: Some(11),
+        difficulty: Some(Difficulty::Hard..=Difficulty::Hard),
+        ..GenerateOptions::new(8, 8)
+    };
+    let puzzle = Nonogram::generate_with(&options).unwrap();
+    let short = SolveOptions {
+        max_passes: Some(1),
+        ..SolveOptions::default()
+    };
+
+    assert_eq!(
+        puzzle.difficulty_with_options(&SolveOptions::default()),
+        Some(Difficulty::Hard)
+    );
+    assert_eq!(puzzle.difficulty_with_options(&short), None);
+}
+
+#[test]
+fn cut_off_ratings_count_as_failed_attempts() {
+    let options = GenerateOptions {
+        seed: Some(11),
+        difficulty: Some(Difficulty::Hard..=Difficulty::Hard),
+        max_attempts: 50,
+        rating: SolveOptions {
+            max_passes: Some(0),
+            ..SolveOptions::default()
+        },
+        ..GenerateOptions::new(8, 8)
+    };
+
+    assert_eq!(Nonogram::generate_with(&options), None);
+}
+
+#[test]
+fn symmetric_patterns_mirror() {
+    for &symmetry in &[
+        Symmetry::Horizontal,
+        Symmetry::Vertical,
+        Symmetry::Rotational,
+    ] {
+        let options = GenerateOptions {
+            seed: Some(3),
+            pattern: Pattern::Symmetric(symmetry),
+            ..GenerateOptions::new(7, 6)
+        };
+        let grid = Nonogram::generate_with(&options).unwrap().completed_grid;
+        let mirrored = match symmetry {
+            Symmetry::Horizontal => grid.slice(s![.., ..;-1]),
+            Symmetry::Vertical => grid.slice(s![..;-1, ..]),
+            Symmetry::Rotational => grid.slice(s![..;-1, ..;-1]),
+        };
+
+        assert_eq!(grid, mirrored);
+    }
+}
+
+#[test]
+fn symmetric_band_search_keeps_symmetry() {
+    let options = GenerateOptions {
+        seed: Some(5),
+        pattern: Pattern::Symmetric(Symmetry::Horizontal),
+        difficulty: Some(Difficulty::Medium..=Difficulty::Hard),
+        ..GenerateOptions::new(8, 8)
+    };
+    let grid = Nonogram::generate_with(&options).unwrap().completed_grid;
+
+    assert_eq!(grid, grid.slice(s![.., ..;-1]));
+}
+
+#[test]
+fn noise_pattern_matches_density() {
+    let options = GenerateOptions {
+        seed: Some(9),
+        density: 0.3,
+        pattern: Pattern::Noise { scale: 4 },
+        ..GenerateOptions::new(20, 10)
+    };
+    let puzzle = Nonogram::generate_with(&options).unwrap();
+
+    assert_eq!(
+        puzzle
+            .completed_grid
+            .iter()
+            .filter(|&&cell| cell == 1)
+            .count(),
+        60
+    );
+    assert_eq!(Nonogram::generate_with(&options), Some(puzzle));
+}
+
+#[test]
+fn smoothing_leaves_fewer_isolated_cells() {
+    let isolated = |pattern| {
+        let options = GenerateOptions {
+            seed: Some(21),
+            pattern,
+            ..GenerateOptions::new(20, 20)
+        };
+        let grid = Nonogram::generate_with(&options).unwrap().completed_grid;
+
+        grid.indexed_iter()
+            .filter(|&((i, j), &cell)| {
+                let neighbours = [
+                    (i.wrapping_sub(1), j),
+                    (i + 1, j),
+                    (i, j.wrapping_sub(1)),
+                    (i, j + 1),
+                ];
+
+                neighbours
+                    .iter()
+                    .all(|&neighbour| grid.get(neighbour) != Some(&cell))
+            })
+            .count()
+    };
+
+    assert!(isolated(Pattern::Smoothed { iterations: 3 }) < isolated(Pattern::Uniform) / 4);
+}
@@ -0,0 +1,69 @@
+use nonogram::{arr2, Nonogram, SolveStatus};
+
+#[test]
+fn givens_finish_an_ambiguous_puzzle() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 0], [0, 1]]));
+
+    assert_eq!(puzzle.solve_with_givens(&[]).status, SolveStatus::Stalled);
+    assert_eq!(
+        puzzle.solve_with_givens(&[((0, 0), true)]).status,
+        SolveStatus::Solved
+    );
+}
+
+#[test]
+fn conflicting_givens_contradict() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 0], [0, 1]]));
+    let givens = [((0, 0), true), ((0, 1), true)];
+
+    assert_eq!(
+        puzzle.solve_with_givens(&givens).status,
+        SolveStatus::Contradiction
+    );
+    assert_eq!(puzzle.minimal_givens(&givens), None);
+}
+
+#[test]
+fn minimal_givens_drop_redundant_cells() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 0], [0, 1]]));
+    let givens = [((0, 0), true), ((1, 1), true), ((0, 1), false)];
+
+    assert_eq!(puzzle.minimal_givens(&givens), Some(vec![((0, 1), false)]));
+}
+
+#[test]
+fn minimal_givens_keep_one_per_ambiguous_region() {
+    let puzzle = Nonogram::from_grid(arr2(&[
+        [1, 0, 0, 0, 0, 0],
+        [0, 1, 0, 0, 0, 0],
+        [0, 0, 1, 1, 1, 1],
+        [0, 0, 0, 0, 1, 0],
+        [0, 0, 0, 0, 0, 1],
+    ]));
+    let givens: Vec<((usize, usize), bool)> = puzzle
+        .completed_grid
+        .indexed_iter()
+        .map(|(cell, &value)| (cell, value == 1))
+        .collect();
+    let minimal = puzzle.minimal_givens(&givens).unwrap();
+
+    assert!(!minimal.is_empty());
+    assert_eq!(
+        puzzle.solve_with_givens(&minimal).status,
+        SolveStatus::Solved
+    );
+
+    for index in 0..minimal.len() {
+        let mut fewer = minimal.clone();
+
+        fewer.remove(index);
+        assert_ne!(puzzle.solve_with_givens(&fewer).status, SolveStatus::Solved);
+    }
+}
+
+#[test]
+fn solvable_puzzles_need_no_givens() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 1, 1], [0, 1, 0], [1, 1, 1]]));
+
+    assert_eq!(puzzle.minimal_givens(&[((1, 1), true)]), Some(vec![]));
+}
@@ -0,0 +1,57 @@
+use nonogram::Nonogram;
+
+use ndarray::{arr1, arr2};
+use std::collections::HashSet;
+
+#[test]
+fn puzzles_with_same_clues_are_equal() {
+    let puzzle1 = Nonogram::from_grid(arr2(&[[1, 0], [0, 1]]));
+    let puzzle2 = Nonogram::from_grid(arr2(&[[0, 1], [1, 0]]));
+
+    assert_ne!(puzzle1.completed_grid, puzzle2.completed_grid);
+    assert_eq!(puzzle1, puzzle2);
+    assert_eq!(puzzle1.clue_fingerprint(), puzzle2.clue_fingerprint());
+
+    let set: HashSet<Nonogram> = vec![puzzle1, puzzle2].into_iter().collect();
+
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn puzzles_with_different_clues_are_not_equal() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 1, 0], [0, 0, 1]]));
+    let mut mismatched = puzzle.clone();
+
+    mismatched.row_segments = arr1(&[vec![1, 1], vec![1]]);
+
+    assert_ne!(puzzle, mismatched);
+    assert_ne!(puzzle.clue_fingerprint(), mismatched.clue_fingerprint());
+}
+
+#[test]
+fn fingerprint_separates_clue_boundaries() {
+    let puzzle1 = Nonogram {
+        row_segments: arr1(&[vec![1, 1], vec![]]),
+        column_segments: arr1(&[vec![], vec![], vec![]]),
+        completed_grid: arr2(&[[0, 0, 0], [0, 0, 0]]),
+    };
+    let puzzle2 = Nonogram {
+        row_segments: arr1(&[vec![1], vec![1]]),
+        ..puzzle1.clone()
+    };
+
+    assert_ne!(puzzle1.clue_fingerprint(), puzzle2.clue_fingerprint());
+}
+
+#[test]
+fn fingerprint_is_stable() {
+    let puzzle = Nonogram::from_grid(arr2(&[
+        [0, 0, 0, 0, 0],
+        [0, 1, 0, 1, 0],
+        [0, 0, 0, 0, 0],
+        [0, 1, 0, 1, 0],
+        [0, 0, 0, 0, 0],
+    ]));
+
+    assert_eq!(puzzle.clue_fingerprint(), 5_567_987_051_404_838_919);
+}
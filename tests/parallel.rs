@@ -0,0 +1,85 @@
+#![cfg(feature = "parallel")]
+
+use nonogram::{arr1, GenerateOptions, Nonogram, SolveStatus};
+
+#[test]
+fn solve_all_keeps_order() {
+    let options = GenerateOptions {
+        seed: Some(3),
+        ..GenerateOptions::new(6, 6)
+    };
+    let puzzles: Vec<Nonogram> = Nonogram::generate_many(20, &options)
+        .into_iter()
+        .map(Option::unwrap)
+        .collect();
+    let results = Nonogram::solve_all(&puzzles);
+
+    assert_eq!(results.len(), puzzles.len());
+
+    for (puzzle, result) in puzzles.iter().zip(results) {
+        assert_eq!(result.status, puzzle.solve().status);
+    }
+}
+
+#[test]
+fn generate_many_is_reproducible() {
+    let options = GenerateOptions {
+        seed: Some(42),
+        require_unique: true,
+        ..GenerateOptions::new(5, 5)
+    };
+    let first = Nonogram::generate_many(16, &options);
+    let second = Nonogram::generate_many(16, &options);
+
+    assert_eq!(first, second);
+    assert!(first.iter().flatten().all(Nonogram::solvable));
+}
+
+#[test]
+fn generate_many_clamps_density() {
+    for &density in &[1.5, f64::NAN] {
+        let options = GenerateOptions {
+            density,
+            ..GenerateOptions::new(4, 4)
+        };
+
+        assert!(Nonogram::generate_many(3, &options)
+            .iter()
+            .all(Option::is_some));
+    }
+}
+
+#[test]
+fn generate_many_gives_distinct_puzzles() {
+    let options = GenerateOptions {
+        seed: Some(7),
+        ..GenerateOptions::new(8, 8)
+    };
+    let puzzles = Nonogram::generate_many(4, &options);
+
+    assert_ne!(puzzles[0], puzzles[1]);
+    assert_ne!(puzzles[2], puzzles[3]);
+}
+
+#[test]
+fn solve_parallel_matches_solve() {
+    let options = GenerateOptions {
+        seed: Some(11),
+        ..GenerateOptions::new(12, 9)
+    };
+
+    for puzzle in Nonogram::generate_many(24, &options).into_iter().flatten() {
+        let sequential = puzzle.solve();
+        let parallel = puzzle.solve_parallel();
+
+        assert_eq!(parallel.status, sequential.status);
+        assert_eq!(parallel.grid, sequential.grid);
+    }
+}
+
+#[test]
+fn solve_parallel_reports_contradictions() {
+    let puzzle = Nonogram::from_clues(arr1(&[vec![2], vec![]]), arr1(&[vec![2], vec![]]));
+
+    assert_eq!(puzzle.solve_parallel().status, SolveStatus::Contradiction);
+}
@@ -0,0 +1,106 @@
+use nonogram::{arr1, arr2, Array1, CancelToken, Nonogram, SolveOptions};
+#[cfg(feature = "std")]
+use nonogram::{Difficulty, GenerateOptions};
+
+#[test]
+fn finds_the_only_solution() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 1, 1], [0, 1, 0], [1, 1, 1]]));
+    let solutions = puzzle.solutions(2);
+
+    assert!(solutions.is_unique());
+    assert_eq!(solutions.grids, vec![puzzle.completed_grid]);
+    assert_eq!(solutions.stats.backtracks, 0);
+    assert_eq!(solutions.stats.probes, 0);
+}
+
+#[test]
+fn counts_every_permutation() {
+    // Each row and column holds one filled cell, so every permutation of six is a solution.
+    let clues = Array1::from(vec![vec![1]; 6]);
+    let puzzle = Nonogram::from_clues(clues.clone(), clues);
+    let solutions = puzzle.solutions(1000);
+
+    assert!(solutions.complete);
+    assert_eq!(solutions.grids.len(), 720);
+    assert!(solutions.stats.probes >= 720);
+}
+
+#[test]
+fn stops_at_the_cap() {
+    let clues = Array1::from(vec![vec![1]; 4]);
+    let puzzle = Nonogram::from_clues(clues.clone(), clues);
+    let solutions = puzzle.solutions(5);
+
+    assert!(!solutions.complete);
+    assert_eq!(solutions.grids.len(), 5);
+}
+
+#[test]
+fn contradictory_clues_have_no_solutions() {
+    let puzzle = Nonogram::from_clues(arr1(&[vec![2], vec![]]), arr1(&[vec![2], vec![]]));
+    let solutions = puzzle.solutions(2);
+
+    assert!(solutions.complete);
+    assert!(solutions.grids.is_empty());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn verifies_puzzles_line_logic_cannot_finish() {
+    let options = GenerateOptions {
+        seed: Some(11),
+        difficulty: Some(Difficulty::Hard..=Difficulty::Hard),
+        ..GenerateOptions::new(8, 8)
+    };
+    let puzzle = Nonogram::generate_with(&options).unwrap();
+    let solutions = puzzle.solutions(2);
+
+    assert!(!puzzle.solvable());
+    assert!(solutions.is_unique());
+    assert_eq!(solutions.grids, vec![puzzle.completed_grid]);
+}
+
+#[test]
+fn budget_leaves_the_search_incomplete() {
+    let clues = Array1::from(vec![vec![1]; 5]);
+    let puzzle = Nonogram::from_clues(clues.clone(), clues);
+    let cancel = CancelToken::new();
+
+    cancel.cancel();
+
+    let cancelled = puzzle.solutions_with_options(
+        200,
+        &SolveOptions {
+            cancel: Some(cancel),
+            ..SolveOptions::default()
+        },
+    );
+    let out_of_passes = puzzle.solutions_with_options(
+        200,
+        &SolveOptions {
+            max_passes: Some(3),
+            ..SolveOptions::default()
+        },
+    );
+
+    assert!(!cancelled.complete);
+    assert!(cancelled.grids.is_empty());
+    assert!(!out_of_passes.complete);
+    assert!(out_of_passes.grids.len() < 120);
+}
+
+#[test]
+fn rejects_grids_the_rows_do_not_allow() {
+    // One branch lets the columns settle every cell while the third row breaks its clue, which
+    // must count as a dead end rather than a second solution.
+    let puzzle = Nonogram::from_grid(arr2(&[
+        [1, 0, 0, 1],
+        [0, 0, 1, 1],
+        [1, 1, 0, 0],
+        [0, 0, 1, 0],
+    ]));
+    let solutions = puzzle.solutions(5);
+
+    assert!(solutions.is_unique());
+    assert_eq!(solutions.grids, vec![puzzle.completed_grid]);
+}
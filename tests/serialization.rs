@@ -1,4 +1,6 @@
-use nonogram::Nonogram;
+#![cfg(feature = "std")]
+
+use nonogram::{Line, Nonogram, NonogramError};
 
 use ndarray::{arr1, arr2};
 
@@ -56,7 +58,7 @@ fn deserialize_invalid_json() {
     let serialized = String::from("{\"checksum\"\"3087051523477295210\",\"height\":5,\"width\":5,\"row_segments\":[[],[1,1],[],[1,1],[]],\"column_segments\":[[],[1,1],[],[1,1],[]],\"completed_grid\":[[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0]]}");
 
     assert_eq!(
-        Nonogram::from_json(&serialized).unwrap_err(),
+        Nonogram::from_json(&serialized).unwrap_err().to_string(),
         "expected `:` at line 1 column 12"
     );
 }
@@ -66,7 +68,7 @@ fn deserialize_invalid_object_field() {
     let serialized = String::from("{\"checksummmm\":\"3087051523477295210\",\"height\":5,\"width\":5,\"row_segments\":[[],[1,1],[],[1,1],[]],\"column_segments\":[[],[1,1],[],[1,1],[]],\"completed_grid\":[[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0]]}");
 
     assert_eq!(
-        Nonogram::from_json(&serialized).unwrap_err(),
+        Nonogram::from_json(&serialized).unwrap_err().to_string(),
         "missing field `checksum` at line 1 column 216"
     );
 }
@@ -76,7 +78,7 @@ fn deserialize_invalid_grid_dimensions() {
     let serialized = String::from("{\"checksum\":\"3087051523477295210\",\"height\":5,\"width\":7,\"row_segments\":[[],[1,1],[],[1,1],[]],\"column_segments\":[[],[1,1],[],[1,1],[]],\"completed_grid\":[[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0]]}");
 
     assert_eq!(
-        Nonogram::from_json(&serialized).unwrap_err(),
+        Nonogram::from_json(&serialized).unwrap_err().to_string(),
         "ShapeError/OutOfBounds: out of bounds indexing"
     );
 }
@@ -86,7 +88,136 @@ fn deserialize_invalid_grid_rows() {
     let serialized = String::from("{\"checksum\":\"3087051523477295210\",\"height\":5,\"width\":5,\"row_segments\":[[],[1,1],[],[1,1],[]],\"column_segments\":[[],[1,1],[],[1,1],[]],\"completed_grid\":[[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0],[0,1,0,1,0,1],[0,0,0,0,0]]}");
 
     assert_eq!(
-        Nonogram::from_json(&serialized).unwrap_err(),
+        Nonogram::from_json(&serialized).unwrap_err().to_string(),
         "ShapeError/IncompatibleShape: incompatible shapes"
     );
 }
+
+#[test]
+fn deserialize_mismatched_clue() {
+    let serialized = String::from("{\"checksum\":\"3087051523477295210\",\"height\":5,\"width\":5,\"row_segments\":[[],[1,1],[],[2],[]],\"column_segments\":[[],[1,1],[],[1,1],[]],\"completed_grid\":[[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0]]}");
+
+    match Nonogram::from_json(&serialized).unwrap_err() {
+        NonogramError::ClueMismatch(line) => assert_eq!(line, Line::Row(3)),
+        e => panic!("unexpected error: {}", e),
+    }
+}
+
+#[test]
+fn deserialize_mismatched_checksum() {
+    let serialized = String::from("{\"checksum\":\"1234\",\"height\":5,\"width\":5,\"row_segments\":[[],[1,1],[],[1,1],[]],\"column_segments\":[[],[1,1],[],[1,1],[]],\"completed_grid\":[[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0]]}");
+
+    match Nonogram::from_json(&serialized).unwrap_err() {
+        NonogramError::ChecksumMismatch { stored, computed } => {
+            assert_eq!(stored, 1234);
+            assert_eq!(computed, 3_087_051_523_477_295_210);
+        }
+        e => panic!("unexpected error: {}", e),
+    }
+}
+
+#[test]
+fn deserialize_invalid_cell() {
+    let serialized = String::from("{\"checksum\":\"3087051523477295210\",\"height\":5,\"width\":5,\"row_segments\":[[],[1,1],[],[1,1],[]],\"column_segments\":[[],[1,1],[],[1,1],[]],\"completed_grid\":[[0,0,0,0,0],[0,1,0,1,0],[0,0,2,0,0],[0,1,0,1,0],[0,0,0,0,0]]}");
+
+    match Nonogram::from_json(&serialized).unwrap_err() {
+        NonogramError::InvalidCell { row, column, value } => {
+            assert_eq!((row, column, value), (2, 2, 2))
+        }
+        e => panic!("unexpected error: {}", e),
+    }
+}
+
+#[test]
+fn serde_deserialize_reports_errors() {
+    let serialized = "{\"checksum\":\"3087051523477295210\",\"height\":5,\"width\":7,\"row_segments\":[[],[1,1],[],[1,1],[]],\"column_segments\":[[],[1,1],[],[1,1],[]],\"completed_grid\":[[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0],[0,1,0,1,0],[0,0,0,0,0]]}";
+
+    assert!(serde_json::from_str::<Nonogram>(serialized).is_err());
+}
+
+#[test]
+fn clue_only_round_trip() {
+    let nonogram = Nonogram::from_clues(arr1(&[vec![2], vec![1]]), arr1(&[vec![2], vec![1]]));
+    let serialized = nonogram.as_json().unwrap();
+
+    assert!(serialized.contains("\"completed_grid\":[]"));
+
+    let deserialized = Nonogram::from_json(&serialized).unwrap();
+
+    assert!(deserialized.is_clue_only());
+    assert_eq!(deserialized, nonogram);
+}
+
+#[test]
+fn clue_only_clue_too_long() {
+    let nonogram = Nonogram::from_clues(arr1(&[vec![1, 1], vec![1]]), arr1(&[vec![1], vec![1]]));
+
+    match nonogram.validate().unwrap_err() {
+        NonogramError::ClueMismatch(line) => assert_eq!(line, Line::Row(0)),
+        e => panic!("unexpected error: {}", e),
+    }
+}
+
+#[cfg(any(feature = "cbor", feature = "msgpack", feature = "bincode"))]
+fn sample_puzzle() -> Nonogram {
+    Nonogram {
+        row_segments: arr1(&[vec![], vec![1, 1], vec![], vec![1, 1], vec![]]),
+        column_segments: arr1(&[vec![], vec![1, 1], vec![], vec![1, 1], vec![]]),
+        completed_grid: arr2(&[
+            [0, 0, 0, 0, 0],
+            [0, 1, 0, 1, 0],
+            [0, 0, 0, 0, 0],
+            [0, 1, 0, 1, 0],
+            [0, 0, 0, 0, 0],
+        ]),
+    }
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn cbor_round_trip() {
+    let puzzle = sample_puzzle();
+    let serialized = puzzle.as_cbor().unwrap();
+    let deserialized = Nonogram::from_cbor(&serialized).unwrap();
+
+    assert!(serialized.len() < puzzle.as_json().unwrap().len());
+    assert_eq!(deserialized.row_segments, puzzle.row_segments);
+    assert_eq!(deserialized.column_segments, puzzle.column_segments);
+    assert_eq!(deserialized.completed_grid, puzzle.completed_grid);
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn msgpack_round_trip() {
+    let puzzle = sample_puzzle();
+    let serialized = puzzle.as_msgpack().unwrap();
+    let deserialized = Nonogram::from_msgpack(&serialized).unwrap();
+
+    assert!(serialized.len() < puzzle.as_json().unwrap().len());
+    assert_eq!(deserialized.row_segments, puzzle.row_segments);
+    assert_eq!(deserialized.column_segments, puzzle.column_segments);
+    assert_eq!(deserialized.completed_grid, puzzle.completed_grid);
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn bincode_round_trip() {
+    let puzzle = sample_puzzle();
+    let serialized = puzzle.as_bincode().unwrap();
+    let deserialized = Nonogram::from_bincode(&serialized).unwrap();
+
+    assert_eq!(deserialized.row_segments, puzzle.row_segments);
+    assert_eq!(deserialized.column_segments, puzzle.column_segments);
+    assert_eq!(deserialized.completed_grid, puzzle.completed_grid);
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn bincode_rejects_truncated_input() {
+    let serialized = sample_puzzle().as_bincode().unwrap();
+
+    match Nonogram::from_bincode(&serialized[..serialized.len() - 4]).unwrap_err() {
+        NonogramError::Bincode(_) => (),
+        e => panic!("unexpected error: {}", e),
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(feature = "std")]
+use nonogram::GenerateOptions;
+use nonogram::{
+    arr2, default_strategies, CellState, Enumeration, Line, LineStrategy, Nonogram, SimpleOverlap,
+    SolveStatus,
+};
+
+#[test]
+#[cfg(feature = "std")]
+fn default_strategies_match_solver() {
+    for seed in 0..20 {
+        let options = GenerateOptions {
+            seed: Some(seed),
+            ..GenerateOptions::new(8, 7)
+        };
+        let puzzle = Nonogram::generate_with(&options).unwrap();
+        let expected = puzzle.solve();
+        let explained = puzzle.solve_with_strategies(&default_strategies());
+
+        assert_eq!(explained.result.status, expected.status);
+        assert_eq!(explained.result.grid, expected.grid);
+    }
+}
+
+#[test]
+fn reports_which_rules_fired() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 1, 1, 1], [1, 0, 0, 0], [1, 0, 1, 1]]));
+    let explained = puzzle.solve_with_strategies(&default_strategies());
+    let used = explained.rules_used();
+
+    assert_eq!(explained.result.status, SolveStatus::Solved);
+    assert!(used["simple overlap"] > 0);
+    assert_eq!(
+        explained.deductions[0].line,
+        Line::Row(0),
+        "the full first row is found by overlap first"
+    );
+    assert_eq!(explained.deductions[0].rule, "simple overlap");
+
+    let cells: usize = explained
+        .deductions
+        .iter()
+        .map(|deduction| deduction.cells.len())
+        .sum();
+
+    assert_eq!(cells, 12);
+}
+
+#[test]
+fn weak_rules_stall() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 0, 0], [0, 0, 1], [0, 1, 0]]));
+    let strategies: Vec<Box<dyn LineStrategy>> = vec![Box::new(SimpleOverlap)];
+    let explained = puzzle.solve_with_strategies(&strategies);
+
+    assert_eq!(explained.result.status, SolveStatus::Stalled);
+    assert!(explained
+        .result
+        .grid
+        .iter()
+        .all(|&cell| cell == CellState::Unknown));
+}
+
+struct NoBlocks;
+
+impl LineStrategy for NoBlocks {
+    fn name(&self) -> &'static str {
+        "no blocks"
+    }
+
+    fn deduce(&self, clue: &[usize], line: &[CellState]) -> Option<Vec<(usize, CellState)>> {
+        if clue.is_empty() {
+            Some((0..line.len()).map(|i| (i, CellState::Empty)).collect())
+        } else {
+            Some(Vec::new())
+        }
+    }
+}
+
+#[test]
+fn custom_strategies_take_priority() {
+    let puzzle = Nonogram::from_grid(arr2(&[[0, 0, 0], [1, 1, 0], [0, 0, 0]]));
+    let strategies: Vec<Box<dyn LineStrategy>> = vec![Box::new(NoBlocks), Box::new(Enumeration)];
+    let explained = puzzle.solve_with_strategies(&strategies);
+
+    assert_eq!(explained.result.status, SolveStatus::Solved);
+    assert_eq!(explained.deductions[0].rule, "no blocks");
+    assert_eq!(explained.rules_used()["no blocks"], 3);
+}
+
+#[test]
+fn contradictions_are_reported() {
+    let puzzle = Nonogram::from_clues(
+        nonogram::arr1(&[vec![2], vec![]]),
+        nonogram::arr1(&[vec![2], vec![]]),
+    );
+
+    assert_eq!(
+        puzzle
+            .solve_with_strategies(&default_strategies())
+            .result
+            .status,
+        SolveStatus::Contradiction
+    );
+}
+
+struct FillEverything;
+
+impl LineStrategy for FillEverything {
+    fn name(&self) -> &'static str {
+        "fill everything"
+    }
+
+    fn deduce(&self, _: &[usize], line: &[CellState]) -> Option<Vec<(usize, CellState)>> {
+        Some((0..line.len()).map(|i| (i, CellState::Filled)).collect())
+    }
+}
+
+#[test]
+fn unsound_rules_cannot_claim_a_solution() {
+    let puzzle = Nonogram::from_grid(arr2(&[[1, 0], [0, 1]]));
+    let strategies: Vec<Box<dyn LineStrategy>> = vec![Box::new(FillEverything)];
+
+    assert_eq!(
+        puzzle.solve_with_strategies(&strategies).result.status,
+        SolveStatus::Contradiction
+    );
+}
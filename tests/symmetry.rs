@@ -0,0 +1,154 @@
+#[cfg(feature = "std")]
+use nonogram::Canonical;
+use nonogram::{Nonogram, Transform};
+
+use ndarray::arr2;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+fn puzzle() -> Nonogram {
+    Nonogram::from_grid(arr2(&[[1, 1, 0, 0], [0, 1, 0, 1], [0, 0, 0, 1]]))
+}
+
+#[test]
+fn canonical_checksum_ignores_rotations_and_reflections() {
+    let original = puzzle();
+
+    for transform in Transform::ALL.iter() {
+        let transformed = Nonogram::from_grid(transform.apply(original.completed_grid.view()));
+
+        assert_eq!(
+            transformed.canonical_checksum(),
+            original.canonical_checksum()
+        );
+        assert_eq!(transformed.canonical_grid(), original.canonical_grid());
+    }
+}
+
+#[test]
+fn canonical_checksum_tells_pictures_apart() {
+    let other = Nonogram::from_grid(arr2(&[[1, 1, 0, 0], [0, 1, 0, 1], [0, 0, 1, 1]]));
+
+    assert_ne!(puzzle().canonical_checksum(), other.canonical_checksum());
+}
+
+#[test]
+fn canonical_checksum_covers_dimensions() {
+    let wide = Nonogram::from_grid(arr2(&[[0, 0, 0, 0, 0, 0]]));
+    let square = Nonogram::from_grid(arr2(&[[0, 0, 0], [0, 0, 0]]));
+
+    assert_ne!(wide.canonical_checksum(), square.canonical_checksum());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn canonical_set_drops_flipped_copies() {
+    let original = puzzle();
+    let flipped =
+        Nonogram::from_grid(Transform::FlipHorizontal.apply(original.completed_grid.view()));
+
+    assert!(original != flipped);
+
+    let other = Nonogram::from_grid(arr2(&[[1, 0, 0, 0], [1, 1, 0, 0], [0, 0, 1, 0]]));
+    let unique: HashSet<Canonical> = vec![original, flipped, other]
+        .into_iter()
+        .map(Canonical::from)
+        .collect();
+
+    assert_eq!(unique.len(), 2);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn canonical_set_tells_clue_only_puzzles_apart() {
+    let clue_only = |puzzle: &Nonogram| {
+        Nonogram::from_clues(puzzle.row_segments.clone(), puzzle.column_segments.clone())
+    };
+    let first = clue_only(&puzzle());
+    let rotated = clue_only(&puzzle().rotate90());
+    let other = clue_only(&Nonogram::from_grid(arr2(&[
+        [1, 0, 0, 0],
+        [1, 1, 0, 0],
+        [0, 0, 1, 0],
+    ])));
+
+    assert_ne!(first.canonical_checksum(), other.canonical_checksum());
+    assert_eq!(first.canonical_checksum(), rotated.canonical_checksum());
+
+    let unique: HashSet<Canonical> = vec![first, rotated, other]
+        .into_iter()
+        .map(Canonical::from)
+        .collect();
+
+    assert_eq!(unique.len(), 2);
+}
+
+#[test]
+fn transforms_rebuild_clues_consistently() {
+    let original = puzzle();
+
+    for &transform in Transform::ALL.iter() {
+        let expected = Nonogram::from_grid(transform.apply(original.completed_grid.view()));
+        let transformed = original.transformed(transform);
+
+        assert_eq!(transformed.completed_grid, expected.completed_grid);
+        assert_eq!(transformed.row_segments, expected.row_segments);
+        assert_eq!(transformed.column_segments, expected.column_segments);
+        assert!(transformed.validate().is_ok());
+    }
+}
+
+#[test]
+fn named_transforms() {
+    let original = puzzle();
+
+    assert_eq!(
+        original.rotate90(),
+        original.transformed(Transform::Rotate90)
+    );
+    assert_eq!(original.rotate180(), original.rotate90().rotate90());
+    assert_eq!(original.flip_horizontal().flip_horizontal(), original);
+    assert_eq!(
+        original.flip_vertical().completed_grid,
+        arr2(&[[0, 0, 0, 1], [0, 1, 0, 1], [1, 1, 0, 0]])
+    );
+    assert_eq!(original.transpose().height(), 4);
+}
+
+#[test]
+fn transforms_clue_only_puzzles() {
+    let original = puzzle();
+    let clues = Nonogram::from_clues(
+        original.row_segments.clone(),
+        original.column_segments.clone(),
+    );
+
+    assert!(clues.is_clue_only());
+
+    for &transform in Transform::ALL.iter() {
+        let transformed = clues.transformed(transform);
+        let expected = original.transformed(transform);
+
+        assert!(transformed.is_clue_only());
+        assert_eq!(transformed.row_segments, expected.row_segments);
+        assert_eq!(transformed.column_segments, expected.column_segments);
+    }
+}
+
+#[test]
+fn solver_is_invariant_under_transforms() {
+    let original = Nonogram::from_grid(arr2(&[
+        [1, 1, 0, 1, 0],
+        [0, 1, 1, 1, 0],
+        [1, 0, 0, 1, 1],
+        [1, 1, 1, 0, 0],
+    ]));
+    let solved = original.solve();
+
+    for &transform in Transform::ALL.iter() {
+        let result = original.transformed(transform).solve();
+
+        assert_eq!(result.status, solved.status);
+        assert_eq!(result.grid, transform.apply(solved.grid.view()));
+    }
+}
@@ -0,0 +1,133 @@
+//! Run with `wasm-pack test --node --features wasm`.
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use js_sys::{BigInt, Reflect};
+use nonogram::{arr1, GenerateOptions, Nonogram, Puzzle, SolveOptions, SolveStatus};
+use std::time::Duration;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn field(object: &JsValue, name: &str) -> JsValue {
+    Reflect::get(object, &JsValue::from_str(name)).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn unseeded_generation_does_not_need_os_entropy() {
+    let puzzle = Puzzle::generate(6, 4, None, None, None).unwrap();
+
+    assert_eq!((puzzle.width(), puzzle.height()), (6, 4));
+}
+
+#[wasm_bindgen_test]
+fn seeded_generation_is_reproducible() {
+    let seed = || Some(JsValue::from(3).unchecked_into());
+    let first = Puzzle::generate(8, 8, seed(), Some(0.6), Some(true)).unwrap();
+    let second = Puzzle::generate(8, 8, seed(), Some(0.6), Some(true)).unwrap();
+
+    assert_eq!(first.to_code(), second.to_code());
+    assert!(Puzzle::generate(3, 3, None, Some(2.0), None).is_err());
+}
+
+#[wasm_bindgen_test]
+fn bigint_seeds_reach_every_rust_seed() {
+    let seed = Some(JsValue::from(BigInt::from(u64::MAX)).unchecked_into());
+    let puzzle = Puzzle::generate(6, 6, seed, None, None).unwrap();
+    let expected = Nonogram::generate_with(&GenerateOptions {
+        seed: Some(u64::MAX),
+        ..GenerateOptions::new(6, 6)
+    })
+    .unwrap();
+
+    assert_eq!(puzzle.to_code(), expected.as_code());
+
+    let too_big = BigInt::from(u64::MAX) + BigInt::from(1u64);
+
+    for seed in [
+        JsValue::from(-1),
+        JsValue::from(1.5),
+        JsValue::from(f64::NAN),
+        JsValue::from(2f64.powi(53)),
+        JsValue::from(BigInt::from(-1i64)),
+        JsValue::from(too_big),
+        JsValue::from_str("7"),
+    ] {
+        assert!(Puzzle::generate(3, 3, Some(seed.unchecked_into()), None, None).is_err());
+    }
+}
+
+#[wasm_bindgen_test]
+fn time_limits_read_the_javascript_clock() {
+    let puzzle = Puzzle::from_code("3x3:64").unwrap();
+    let options = SolveOptions {
+        max_time: Some(Duration::from_secs(60)),
+        ..SolveOptions::default()
+    };
+    let result = Nonogram::from_code("3x3:64")
+        .unwrap()
+        .solve_with_options(&options);
+
+    assert_eq!(result.status, SolveStatus::Solved);
+    assert!(result.stats.elapsed < Duration::from_secs(60));
+    assert!(puzzle.validate().is_ok());
+}
+
+#[wasm_bindgen_test]
+fn json_and_codes_round_trip() {
+    let puzzle = Puzzle::from_code("4x2:gQ").unwrap();
+    let json = puzzle.to_json().unwrap();
+
+    assert_eq!(puzzle.to_code().as_deref(), Some("4x2:gQ"));
+    assert_eq!(Puzzle::from_json(&json).unwrap().to_json().unwrap(), json);
+    assert_eq!(puzzle.solution(), Some(vec![1, 0, 0, 0, 0, 0, 0, 1]));
+    assert!(puzzle.validate().is_ok());
+    assert!(Puzzle::from_code("4x2:g").is_err());
+}
+
+#[wasm_bindgen_test]
+fn solve_reports_status_and_cells() {
+    let puzzle = Puzzle::from_code("3x3:64").unwrap();
+    let outcome: JsValue = puzzle.solve().into();
+
+    assert_eq!(field(&outcome, "status"), "solved");
+    assert_eq!(js_sys::Array::from(&field(&outcome, "cells")).length(), 9);
+}
+
+#[wasm_bindgen_test]
+fn hints_point_at_mistakes_and_deductions() {
+    // 111/010/111
+    let puzzle = Puzzle::from_code("3x3:64").unwrap();
+    let mut cells = vec![-1; 9];
+
+    cells[3] = 1;
+    let hint: JsValue = puzzle.hint(&cells).unwrap().into();
+
+    assert_eq!(field(&hint, "kind"), "mistake");
+    assert_eq!(field(&hint, "row"), 1);
+    assert_eq!(field(&hint, "column"), 0);
+
+    cells[3] = -1;
+    let hint: JsValue = puzzle.hint(&cells).unwrap().into();
+
+    assert_eq!(field(&hint, "kind"), "deduction");
+    assert_eq!(field(&hint, "state"), 1);
+
+    let solved = puzzle.solution().unwrap();
+    let hint: JsValue = puzzle.hint(&solved).unwrap().into();
+
+    assert!(hint.is_undefined());
+    assert!(puzzle.is_complete(&solved).unwrap());
+    assert!(puzzle.hint(&[0; 4]).is_err());
+    assert!(puzzle.hint(&[2; 9]).is_err());
+}
+
+#[wasm_bindgen_test]
+fn clue_only_puzzles_get_hints() {
+    // Two solutions, so no single line decides anything and the hint reveals a cell.
+    let clues = arr1(&[vec![1], vec![1]]);
+    let puzzle = Puzzle::from(Nonogram::from_clues(clues.clone(), clues));
+    let hint: JsValue = puzzle.hint(&[-1; 4]).unwrap().into();
+
+    assert_eq!(field(&hint, "kind"), "reveal");
+    assert!(puzzle.solution().is_none());
+    assert!(puzzle.to_code().is_none());
+}